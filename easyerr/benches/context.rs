@@ -0,0 +1,90 @@
+//! Cost of adding context on the happy path, where building the error should stay out of the way.
+
+use std::{hint::black_box, num::ParseIntError};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use easyerr::prelude::*;
+
+#[derive(Debug, Error)]
+#[error("invalid number {input:?} on line {line}")]
+pub struct NumberError {
+    source: ParseIntError,
+    input: String,
+    line: usize,
+}
+
+#[derive(Debug, Error)]
+#[error("invalid number on line {line}")]
+pub struct LineError {
+    source: ParseIntError,
+    line: usize,
+}
+
+fn parse_context(lines: &[&str]) -> Result<u64, LineError> {
+    let mut sum = 0;
+    for (line, input) in lines.iter().enumerate() {
+        let n: u64 = input.parse().context(LineCtx { line })?;
+        sum += n;
+    }
+
+    Ok(sum)
+}
+
+fn parse_with_context(lines: &[&str]) -> Result<u64, NumberError> {
+    let mut sum = 0;
+    for (line, input) in lines.iter().enumerate() {
+        let n: u64 = input.parse().with_context(|_| NumberCtx {
+            input: (*input).to_owned(),
+            line,
+        })?;
+        sum += n;
+    }
+
+    Ok(sum)
+}
+
+fn parse_map_err(lines: &[&str]) -> Result<u64, NumberError> {
+    let mut sum = 0;
+    for (line, input) in lines.iter().enumerate() {
+        let n: u64 = input.parse().map_err(|source| NumberError {
+            source,
+            input: (*input).to_owned(),
+            line,
+        })?;
+        sum += n;
+    }
+
+    Ok(sum)
+}
+
+fn parse_unchecked(lines: &[&str]) -> u64 {
+    lines
+        .iter()
+        .map(|input| input.parse::<u64>().unwrap_or(0))
+        .sum()
+}
+
+fn happy_path(c: &mut Criterion) {
+    let lines: Vec<String> = (0..1024).map(|i| (i * 7919).to_string()).collect();
+    let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+
+    let mut group = c.benchmark_group("happy_path");
+    group.bench_function("context", |b| b.iter(|| parse_context(black_box(&lines))));
+    group.bench_function("with_context", |b| {
+        b.iter(|| parse_with_context(black_box(&lines)));
+    });
+    group.bench_function("map_err", |b| b.iter(|| parse_map_err(black_box(&lines))));
+    group.bench_function("no_context", |b| {
+        b.iter(|| parse_unchecked(black_box(&lines)));
+    });
+    group.finish();
+}
+
+fn error_path(c: &mut Criterion) {
+    c.bench_function("error_path", |b| {
+        b.iter(|| parse_with_context(black_box(&["1", "2", "three"])));
+    });
+}
+
+criterion_group!(benches, happy_path, error_path);
+criterion_main!(benches);
@@ -0,0 +1,29 @@
+//! Example "explain" binary. A real project would link its own errors instead of defining them
+//! here, e.g. by depending on the crates which define them.
+//!
+//! ```text
+//! cargo run --example easyerr-explain --features catalog,std -- --explain E0001
+//! ```
+
+use easyerr::Error;
+
+/// The message was shorter than the minimum length of 20 bytes.
+#[derive(Debug, Error)]
+#[error("message is too short: {f0} bytes", code = "E0001")]
+pub struct TooShortError(pub usize);
+
+#[derive(Debug, Error)]
+pub enum OpenError {
+    /// The file could not be read. Check that it exists and that you have permission to read it.
+    #[error("failed to read message from {path}", code = "E0002")]
+    Read {
+        source: std::io::Error,
+        path: String,
+    },
+    #[error(transparent, code = "E0003")]
+    TooShort { source: TooShortError },
+}
+
+fn main() -> std::process::ExitCode {
+    easyerr::catalog::cli()
+}
@@ -0,0 +1,132 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::{any::Any, error::Error, fmt};
+
+/// An [`Error`] with some values attached to it.
+///
+/// Created by [`ResultExt::attach`](crate::ResultExt::attach) and
+/// [`ResultExt::attach_with`](crate::ResultExt::attach_with). Attachments are any `Display` value
+/// (row numbers, paths, request ids...) which is useful to understand an error but not worth a
+/// context type of its own. They can be retrieved by type with [`request_ref`](Self::request_ref).
+///
+/// The wrapped error is the [`source`](Error::source) of an `Attached`, so its message is not
+/// repeated in the message of the `Attached` itself: `{}` only prints the attachments, separated by
+/// `, `. In alternate mode (`{:#}`), the chain of sources follows, starting with the wrapped error.
+///
+/// Calling [`ResultExt::attach`](crate::ResultExt::attach) on a result whose error is already an
+/// `Attached` wraps it again, giving an `Attached<Attached<E>>` with two layers in its chain. Use
+/// [`Attached::attach`] (e.g. through `map_err`) to add to the existing attachments instead.
+pub struct Attached<E> {
+    error: E,
+    attachments: Vec<Box<dyn Attachment>>,
+}
+
+/// Object safe helper for attachments, so they can be both displayed and downcast.
+trait Attachment: fmt::Display + Send + Sync + 'static {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<A: fmt::Display + Send + Sync + 'static> Attachment for A {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl<E> Attached<E> {
+    /// Attaches `value` to `error`.
+    pub fn new<A>(error: E, value: A) -> Self
+    where
+        A: fmt::Display + Send + Sync + 'static,
+    {
+        Self {
+            error,
+            attachments: Vec::new(),
+        }
+        .attach(value)
+    }
+
+    /// Attaches another value to this error.
+    pub fn attach<A>(mut self, value: A) -> Self
+    where
+        A: fmt::Display + Send + Sync + 'static,
+    {
+        self.attachments.push(Box::new(value));
+        self
+    }
+
+    /// Attaches another value to this error, lazily.
+    pub fn attach_with<A, F>(self, f: F) -> Self
+    where
+        A: fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> A,
+    {
+        self.attach(f())
+    }
+
+    /// Returns the most recently attached value of type `T`, if any.
+    pub fn request_ref<T: 'static>(&self) -> Option<&T> {
+        self.attachments
+            .iter()
+            .rev()
+            // `Box<dyn Attachment>` is an `Attachment` itself, so deref to reach the value.
+            .find_map(|a| (**a).as_any().downcast_ref())
+    }
+
+    /// Returns all the attachments, in the order they were attached.
+    pub fn attachments(&self) -> impl Iterator<Item = &dyn fmt::Display> {
+        self.attachments.iter().map(|a| a as &dyn fmt::Display)
+    }
+
+    /// Returns the wrapped error.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Unwraps this error, dropping the attachments.
+    pub fn into_error(self) -> E {
+        self.error
+    }
+}
+
+impl<E: Error + 'static> fmt::Display for Attached<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, attachment) in self.attachments.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{attachment}")?;
+        }
+
+        if f.alternate() {
+            crate::__private::fmt_chain(f, Some(&self.error))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for Attached<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Attachments<'a>(&'a [Box<dyn Attachment>]);
+
+        impl fmt::Debug for Attachments<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut list = f.debug_list();
+                for attachment in self.0 {
+                    list.entry(&format_args!("{attachment}"));
+                }
+                list.finish()
+            }
+        }
+
+        f.debug_struct("Attached")
+            .field("error", &self.error)
+            .field("attachments", &Attachments(&self.attachments))
+            .finish()
+    }
+}
+
+impl<E: Error + 'static> Error for Attached<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
@@ -0,0 +1,39 @@
+// The derive calls trait methods on sources and fields of any type through autoref-based
+// specialization. Method resolution tries the receiver as written before dereferencing it, so in
+// `(&&XRef(value)).method()` an impl for `&XRef<T>`, whose `&self` is `&&XRef<T>` and which only
+// exists where `T` implements the trait, wins over a fallback impl for `XRef<T>`, which always
+// exists but is only reached after one dereference. Both traits must be in scope, which the derive
+// does with `use ... as _` in every method it generates. This only works because the type of
+// `value` is concrete where the derive expands, not in generic code.
+//
+// `dyn_ref!` defines such a pair of traits giving a value as `Option<&dyn Trait>`. The fields in
+// `field.rs` and the `io::Error` conversion in `io.rs` use the same technique, with more levels or
+// by value.
+
+/// Defines `$ref`, `$via` and `$no`, with which `(&&$ref(value)).$method()` is `Some` if the type of
+/// `value` implements `$trait` and `None` otherwise.
+macro_rules! dyn_ref {
+    ($ref:ident, $via:ident, $no:ident, $method:ident: $trait:ident) => {
+        pub struct $ref<'a, T>(pub &'a T);
+
+        pub trait $via<'a> {
+            fn $method(&self) -> Option<&'a dyn $trait>;
+        }
+
+        impl<'a, T: $trait> $via<'a> for &$ref<'a, T> {
+            fn $method(&self) -> Option<&'a dyn $trait> {
+                Some(self.0)
+            }
+        }
+
+        pub trait $no<'a> {
+            fn $method(&self) -> Option<&'a dyn $trait>;
+        }
+
+        impl<'a, T> $no<'a> for $ref<'a, T> {
+            fn $method(&self) -> Option<&'a dyn $trait> {
+                None
+            }
+        }
+    };
+}
@@ -0,0 +1,218 @@
+//! Catalog of every error type deriving [`Error`](crate::Error) which is linked into the current
+//! binary.
+//!
+//! The derive registers each struct and enum variant in a link-time registry, together with its
+//! message template, doc comments and error code. This module can then dump the catalog as
+//! Markdown or JSON, or explain a single error - similar to `rustc --explain`.
+
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+/// A struct or enum variant registered in the catalog.
+#[derive(Debug)]
+pub struct Entry {
+    /// Path of the module the error type was defined in.
+    pub module: &'static str,
+    /// Name of the error type.
+    pub type_name: &'static str,
+    /// Name of the variant, if the error type is an enum.
+    pub variant: Option<&'static str>,
+    /// Message template of the error, or `None` if it is `transparent`.
+    pub message: Option<&'static str>,
+    /// Doc comments of the error type, or of the variant if the type is an enum.
+    pub docs: &'static str,
+    /// Error code, if any.
+    pub code: Option<&'static str>,
+}
+
+inventory::collect!(Entry);
+
+impl Entry {
+    /// Whether this entry is identified by `name`, which is either `Type`, `Type::Variant`, a
+    /// path such as `module::Type::Variant` or an error code.
+    pub fn matches(&self, name: &str) -> bool {
+        if self.code == Some(name) {
+            return true;
+        }
+
+        let name = match self.variant {
+            Some(variant) => match name.strip_suffix(variant) {
+                Some(rest) => match rest.strip_suffix("::") {
+                    Some(rest) => rest,
+                    None => return false,
+                },
+                None => return false,
+            },
+            None => name,
+        };
+
+        name == self.type_name
+            || name
+                .strip_suffix(self.type_name)
+                .and_then(|module| module.strip_suffix("::"))
+                .is_some_and(|module| module == self.module)
+    }
+
+    /// An adapter which displays the path of this entry, i.e. `Type` or `Type::Variant`.
+    pub fn path(&self) -> impl fmt::Display + '_ {
+        struct Path<'a>(&'a Entry);
+
+        impl fmt::Display for Path<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.0.type_name)?;
+                if let Some(variant) = self.0.variant {
+                    write!(f, "::{variant}")?;
+                }
+
+                Ok(())
+            }
+        }
+
+        Path(self)
+    }
+}
+
+/// Displays the explanation of the error, in Markdown.
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "## `{}`", self.path())?;
+        if let Some(code) = self.code {
+            write!(f, " ({code})")?;
+        }
+
+        write!(f, "\n\ndefined in `{}`\n\n", self.module)?;
+        match self.message {
+            Some(message) => writeln!(f, "message: `{message}`")?,
+            None => writeln!(f, "message: transparent")?,
+        }
+
+        if !self.docs.is_empty() {
+            write!(f, "\n{}\n", self.docs)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns every entry in the catalog, sorted by module, type and variant.
+pub fn entries() -> Vec<&'static Entry> {
+    let mut entries: Vec<_> = inventory::iter::<Entry>.into_iter().collect();
+    entries.sort_by_key(|e| (e.module, e.type_name, e.variant));
+    entries
+}
+
+/// Finds the entry identified by `name`. See [`Entry::matches`].
+pub fn find(name: &str) -> Option<&'static Entry> {
+    inventory::iter::<Entry>
+        .into_iter()
+        .find(|e| e.matches(name))
+}
+
+/// Displays the whole catalog as Markdown.
+pub struct Markdown;
+
+impl fmt::Display for Markdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("# Errors\n")?;
+        for entry in entries() {
+            write!(f, "\n{entry}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Displays the whole catalog as a JSON array.
+pub struct Json;
+
+fn write_json_str(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", u32::from(c))?,
+            c => f.write_char(c)?,
+        }
+    }
+
+    f.write_char('"')
+}
+
+fn write_json_opt(f: &mut fmt::Formatter<'_>, s: Option<&str>) -> fmt::Result {
+    match s {
+        Some(s) => write_json_str(f, s),
+        None => f.write_str("null"),
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('[')?;
+        for (i, entry) in entries().into_iter().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+
+            f.write_str("{\"module\":")?;
+            write_json_str(f, entry.module)?;
+            f.write_str(",\"type\":")?;
+            write_json_str(f, entry.type_name)?;
+            f.write_str(",\"variant\":")?;
+            write_json_opt(f, entry.variant)?;
+            f.write_str(",\"message\":")?;
+            write_json_opt(f, entry.message)?;
+            f.write_str(",\"docs\":")?;
+            write_json_str(f, entry.docs)?;
+            f.write_str(",\"code\":")?;
+            write_json_opt(f, entry.code)?;
+            f.write_char('}')?;
+        }
+
+        f.write_char(']')
+    }
+}
+
+/// Small command line interface for the catalog, meant to be called from the `main` function of
+/// an "explain" binary which links the errors of the project.
+///
+/// - `--explain <NAME>` prints the explanation of a single error (see [`Entry::matches`])
+/// - `--json` prints the whole catalog as JSON
+/// - otherwise, the whole catalog is printed as Markdown
+#[cfg(feature = "std")]
+pub fn cli() -> std::process::ExitCode {
+    use std::{eprintln, print, println, process::ExitCode};
+
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    match args
+        .iter()
+        .map(alloc::string::String::as_str)
+        .collect::<Vec<_>>()[..]
+    {
+        ["--explain", name] => match find(name) {
+            Some(entry) => {
+                print!("{entry}");
+                ExitCode::SUCCESS
+            }
+            None => {
+                eprintln!("error: no error named `{name}` in the catalog");
+                ExitCode::FAILURE
+            }
+        },
+        ["--json"] => {
+            println!("{Json}");
+            ExitCode::SUCCESS
+        }
+        [] | ["--markdown"] => {
+            print!("{Markdown}");
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("usage: [--explain <NAME> | --json | --markdown]");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use alloc::borrow::Cow;
+use core::{error::Error, fmt};
+
+use crate::ErrorContext;
+
+/// An [`Error`] made of an ad-hoc message and a source.
+///
+/// Created by [`ResultExt::describe`](crate::ResultExt::describe) and
+/// [`ResultExt::describe_with`](crate::ResultExt::describe_with), for when defining a whole
+/// context type for a step is overkill (e.g. in scripts). Like derived errors, `{}` only displays
+/// the message, and `{:#}` appends the chain of sources.
+///
+/// A `Described` can be the source of a derived error, so a `#[error(transparent)]` variant can
+/// wrap it later on.
+pub struct Described<E> {
+    message: Cow<'static, str>,
+    source: E,
+}
+
+impl<E> Described<E> {
+    /// Describes `source` with `message`.
+    pub fn new(message: impl Into<Cow<'static, str>>, source: E) -> Self {
+        Self {
+            message: message.into(),
+            source,
+        }
+    }
+
+    /// Message describing the source.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the described error.
+    pub fn inner(&self) -> &E {
+        &self.source
+    }
+
+    /// Unwraps this error, dropping the message.
+    pub fn into_inner(self) -> E {
+        self.source
+    }
+}
+
+impl<E: Error + 'static> fmt::Display for Described<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        if f.alternate() {
+            crate::__private::fmt_chain(f, Some(&self.source))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for Described<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Described")
+            .field("message", &self.message)
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl<E: Error + 'static> Error for Described<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// [`ErrorContext`] which turns any error `E` into a [`Described<E>`].
+pub struct DescribedCtx {
+    message: Cow<'static, str>,
+}
+
+impl DescribedCtx {
+    /// Context describing its source with `message`.
+    pub fn new(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl<E: Error + 'static> ErrorContext<E> for DescribedCtx {
+    type Err = Described<E>;
+
+    fn add_to_source(self, source: E) -> Described<E> {
+        Described {
+            message: self.message,
+            source,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+// Used by the derive to expose sources as diagnostics.
+
+use miette::Diagnostic;
+
+dyn_ref!(DiagnosticRef, ViaDiagnostic, NoDiagnostic, diagnostic: Diagnostic);
@@ -0,0 +1,75 @@
+use core::error::Error;
+
+/// Trait for errors which map to a process exit code.
+///
+/// The derive implements this trait for every type, using the `#[error(exit_code = 74)]` argument
+/// of each struct or variant. Those without an exit code use `1`, except for `transparent` ones,
+/// which use the exit code of their source if it implements this trait. Other errors can opt into
+/// the default with an empty impl.
+pub trait ExitCodeError: Error {
+    /// Exit code of the process when it terminates because of this error.
+    fn exit_code(&self) -> u8 {
+        1
+    }
+}
+
+// Used by the derive to forward exit codes to sources.
+dyn_ref!(ExitCodeRef, ViaExitCode, NoExitCode, exit_code_error: ExitCodeError);
+
+#[cfg(feature = "std")]
+pub use self::std_impl::Exit;
+
+#[cfg(feature = "std")]
+mod std_impl {
+    use super::ExitCodeError;
+    use core::fmt;
+    use std::process::{ExitCode, Termination};
+
+    /// Return type for `main` which, on error, prints the error and its sources to stderr and
+    /// exits with the [exit code](ExitCodeError::exit_code) of the error.
+    ///
+    /// ```no_run
+    /// # use easyerr::{Error, Exit};
+    /// #[derive(Debug, Error)]
+    /// #[error("failed to read input", exit_code = 74)]
+    /// struct InputError {
+    ///     source: std::io::Error,
+    /// }
+    ///
+    /// fn run() -> Result<(), InputError> {
+    ///     # Ok(())
+    /// }
+    ///
+    /// fn main() -> Exit<InputError> {
+    ///     Exit(run())
+    /// }
+    /// ```
+    #[derive(Debug)]
+    pub struct Exit<E>(pub Result<(), E>);
+
+    impl<E> From<Result<(), E>> for Exit<E> {
+        fn from(value: Result<(), E>) -> Self {
+            Self(value)
+        }
+    }
+
+    struct Report<'a, E>(&'a E);
+
+    impl<E: ExitCodeError> fmt::Display for Report<'_, E> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            crate::__private::fmt_report(f, self.0)
+        }
+    }
+
+    impl<E: ExitCodeError> Termination for Exit<E> {
+        fn report(self) -> ExitCode {
+            match self.0 {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(err) => {
+                    std::eprintln!("Error: {}", Report(&err));
+                    ExitCode::from(err.exit_code())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Helpers for exposing errors through a C ABI.
+//!
+//! C callers usually only get an integer back from a failed call. This module keeps the message of
+//! the last error reported on each thread, so they can fetch it afterwards:
+//!
+//! ```
+//! # use easyerr::{ffi, Error};
+//! # use core::ffi::c_char;
+//! #[derive(Debug, Error)]
+//! enum DbError {
+//!     #[error("database is locked", errno = -1)]
+//!     Locked,
+//! }
+//!
+//! fn open() -> Result<(), DbError> {
+//!     Err(DbError::Locked)
+//! }
+//!
+//! #[no_mangle]
+//! pub extern "C" fn db_open() -> i32 {
+//!     match open() {
+//!         Ok(()) => 0,
+//!         Err(err) => ffi::report(&err),
+//!     }
+//! }
+//!
+//! #[no_mangle]
+//! pub unsafe extern "C" fn db_last_error(buf: *mut c_char, len: usize) -> isize {
+//!     unsafe { ffi::copy_last_error(buf, len) }
+//! }
+//! # assert_eq!(db_open(), -1);
+//! # assert_eq!(ffi::last_error_message().unwrap(), "database is locked");
+//! ```
+
+use crate::RawError;
+use core::{cell::RefCell, error::Error, ffi::c_char};
+use std::{ffi::CString, string::String};
+
+std::thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Stores the message of `err`, including its sources, as the last error of this thread.
+pub fn set_last_error(err: &dyn Error) {
+    let mut message = std::format!("{err:#}");
+    message.retain(|c| c != '\0');
+
+    let message = CString::new(message).expect("nul bytes were removed");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Stores `err` as the last error of this thread and returns its raw code.
+pub fn report<E: RawError>(err: &E) -> i32 {
+    set_last_error(err);
+    err.as_raw()
+}
+
+/// Clears the last error of this thread.
+pub fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// The message of the last error of this thread, if any.
+pub fn last_error_message() -> Option<String> {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map(|m| m.to_string_lossy().into_owned())
+    })
+}
+
+/// Length in bytes of the message of the last error of this thread, including the nul terminator,
+/// or `0` if there is none.
+pub fn last_error_length() -> usize {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(0, |m| m.as_bytes_with_nul().len())
+    })
+}
+
+/// Copies the nul-terminated message of the last error of this thread into `buf` and returns the
+/// number of bytes written, including the nul terminator.
+///
+/// Returns `0` if there is no last error and `-1` if `buf` is null or too small, in which case
+/// nothing is written.
+///
+/// # Safety
+/// `buf` must either be null or valid for writes of `len` bytes.
+pub unsafe fn copy_last_error(buf: *mut c_char, len: usize) -> isize {
+    LAST_ERROR.with(|last| {
+        let last = last.borrow();
+        let Some(message) = last.as_ref() else {
+            return 0;
+        };
+
+        let bytes = message.as_bytes_with_nul();
+        if buf.is_null() || bytes.len() > len {
+            return -1;
+        }
+
+        // SAFETY: `buf` is valid for writes of `len >= bytes.len()` bytes, and it can't overlap
+        // with `bytes`, which is owned by the thread local.
+        unsafe { core::ptr::copy_nonoverlapping(bytes.as_ptr().cast(), buf, bytes.len()) };
+        isize::try_from(bytes.len()).unwrap_or(isize::MAX)
+    })
+}
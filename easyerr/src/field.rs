@@ -0,0 +1,218 @@
+use core::{
+    error::Error,
+    fmt::{self, Debug, Display},
+};
+
+/// Visitor for the fields of an error. See [`ErrorFields`].
+pub trait FieldVisitor {
+    /// Records a field through its [`Display`] impl.
+    fn record_display(&mut self, name: &'static str, value: &dyn Display);
+
+    /// Records a field which doesn't implement [`Display`] through its [`Debug`] impl.
+    fn record_debug(&mut self, name: &'static str, value: &dyn Debug);
+
+    /// Records a signed integer field. Defaults to [`record_display`](Self::record_display).
+    fn record_i64(&mut self, name: &'static str, value: i64) {
+        self.record_display(name, &value);
+    }
+
+    /// Records an unsigned integer field. Defaults to [`record_display`](Self::record_display).
+    fn record_u64(&mut self, name: &'static str, value: u64) {
+        self.record_display(name, &value);
+    }
+
+    /// Records a floating point field. Defaults to [`record_display`](Self::record_display).
+    fn record_f64(&mut self, name: &'static str, value: f64) {
+        self.record_display(name, &value);
+    }
+
+    /// Records a boolean field. Defaults to [`record_display`](Self::record_display).
+    fn record_bool(&mut self, name: &'static str, value: bool) {
+        self.record_display(name, &value);
+    }
+
+    /// Records a string field. Defaults to [`record_display`](Self::record_display).
+    fn record_str(&mut self, name: &'static str, value: &str) {
+        self.record_display(name, &value);
+    }
+}
+
+/// Trait for errors whose fields can be inspected, e.g. to feed them into telemetry.
+///
+/// The derive implements this trait for every type. Every field of the active struct or variant is
+/// recorded with its name (or its index, for tuple fields): integers, floats, booleans and strings
+/// through the matching typed method of [`FieldVisitor`], other fields through their [`Display`]
+/// impl if they have one and through their [`Debug`] impl otherwise. The source, fields implementing
+/// neither and fields marked with `#[error(skip)]` are skipped, and redacted fields stay redacted
+/// unless visited inside an [`unredacted`](crate::unredacted) scope.
+pub trait ErrorFields: Error {
+    /// Calls `visitor` with every field of this error.
+    fn visit_fields(&self, visitor: &mut dyn FieldVisitor);
+}
+
+// The items below are used by the derive to expose fields of any type, through autoref-based
+// specialization (see `autoref.rs`): `(&&&&&FieldRef(value)).field()` resolves to the first of the `Via*` impls which
+// applies to the type of `value`.
+
+/// Values implementing both [`Display`] and [`Debug`].
+pub trait Printable: Display + Debug {}
+
+impl<T: Display + Debug + ?Sized> Printable for T {}
+
+/// A field, recorded as a primitive value, through its [`Display`] impl, its [`Debug`] impl or
+/// neither.
+pub enum Field<'a> {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Bool(bool),
+    Str(&'a str),
+    Printable(&'a dyn Printable),
+    Display(&'a dyn Display),
+    Debug(&'a dyn Debug),
+    Opaque,
+}
+
+impl Display for Field<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::I64(value) => Display::fmt(value, f),
+            Self::U64(value) => Display::fmt(value, f),
+            Self::F64(value) => Display::fmt(value, f),
+            Self::Bool(value) => Display::fmt(value, f),
+            Self::Str(value) => f.write_str(value),
+            Self::Printable(value) => Display::fmt(value, f),
+            Self::Display(value) => Display::fmt(value, f),
+            Self::Debug(value) => Debug::fmt(value, f),
+            Self::Opaque => f.write_str("<opaque>"),
+        }
+    }
+}
+
+/// Renders the field like the [`Debug`] impl of its value, which is what redacted fields are hashed
+/// from. Fields which only implement [`Display`] are rendered through it instead.
+impl Debug for Field<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::I64(value) => Debug::fmt(value, f),
+            Self::U64(value) => Debug::fmt(value, f),
+            Self::F64(value) => Debug::fmt(value, f),
+            Self::Bool(value) => Debug::fmt(value, f),
+            Self::Str(value) => Debug::fmt(value, f),
+            Self::Printable(value) => Debug::fmt(value, f),
+            Self::Display(value) => Display::fmt(value, f),
+            Self::Debug(value) => Debug::fmt(value, f),
+            Self::Opaque => f.write_str("<opaque>"),
+        }
+    }
+}
+
+impl Field<'_> {
+    /// Records this field with `visitor`, unless it is opaque.
+    pub fn record(&self, name: &'static str, visitor: &mut dyn FieldVisitor) {
+        match *self {
+            Self::I64(value) => visitor.record_i64(name, value),
+            Self::U64(value) => visitor.record_u64(name, value),
+            Self::F64(value) => visitor.record_f64(name, value),
+            Self::Bool(value) => visitor.record_bool(name, value),
+            Self::Str(value) => visitor.record_str(name, value),
+            Self::Printable(value) => visitor.record_display(name, value),
+            Self::Display(value) => visitor.record_display(name, value),
+            Self::Debug(value) => visitor.record_debug(name, value),
+            Self::Opaque => (),
+        }
+    }
+}
+
+pub struct FieldRef<'a, T>(pub &'a T);
+
+/// Types which are recorded as a primitive value rather than through their [`Display`] impl.
+pub trait Primitive {
+    fn field(&self) -> Field<'_>;
+}
+
+macro_rules! primitive {
+    ($variant:ident($as:ty): $($ty:ty),*) => {
+        $(
+            impl Primitive for $ty {
+                fn field(&self) -> Field<'_> {
+                    Field::$variant(*self as $as)
+                }
+            }
+        )*
+    };
+}
+
+primitive!(I64(i64): i8, i16, i32, i64, isize);
+primitive!(U64(u64): u8, u16, u32, u64, usize);
+primitive!(F64(f64): f32, f64);
+primitive!(Bool(bool): bool);
+
+impl Primitive for str {
+    fn field(&self) -> Field<'_> {
+        Field::Str(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Primitive for alloc::string::String {
+    fn field(&self) -> Field<'_> {
+        Field::Str(self)
+    }
+}
+
+impl<T: Primitive + ?Sized> Primitive for &T {
+    fn field(&self) -> Field<'_> {
+        T::field(self)
+    }
+}
+
+pub trait ViaPrimitive<'a> {
+    fn field(&self) -> Field<'a>;
+}
+
+impl<'a, T: Primitive> ViaPrimitive<'a> for &&&&FieldRef<'a, T> {
+    fn field(&self) -> Field<'a> {
+        Primitive::field(self.0)
+    }
+}
+
+pub trait ViaPrintable<'a> {
+    fn field(&self) -> Field<'a>;
+}
+
+impl<'a, T: Printable> ViaPrintable<'a> for &&&FieldRef<'a, T> {
+    fn field(&self) -> Field<'a> {
+        Field::Printable(self.0)
+    }
+}
+
+pub trait ViaDisplay<'a> {
+    fn field(&self) -> Field<'a>;
+}
+
+impl<'a, T: Display> ViaDisplay<'a> for &&FieldRef<'a, T> {
+    fn field(&self) -> Field<'a> {
+        Field::Display(self.0)
+    }
+}
+
+pub trait ViaDebug<'a> {
+    fn field(&self) -> Field<'a>;
+}
+
+impl<'a, T: Debug> ViaDebug<'a> for &FieldRef<'a, T> {
+    fn field(&self) -> Field<'a> {
+        Field::Debug(self.0)
+    }
+}
+
+pub trait Opaque<'a> {
+    fn field(&self) -> Field<'a>;
+}
+
+impl<'a, T> Opaque<'a> for FieldRef<'a, T> {
+    fn field(&self) -> Field<'a> {
+        Field::Opaque
+    }
+}
@@ -0,0 +1,127 @@
+use core::fmt;
+
+/// Kind of a hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HintKind {
+    Help,
+    Note,
+}
+
+impl fmt::Display for HintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Help => "help",
+            Self::Note => "note",
+        })
+    }
+}
+
+/// Trait for errors with hints for the end user, such as "help: check that the file exists".
+///
+/// The derive implements this trait for every type, with the hints given by the
+/// `#[error(help = "...")]` and `#[error(note = "...")]` arguments of each struct or variant.
+/// `transparent` variants use the hints of their source. When any layer of the chain has hints, the
+/// alternate display (`{:#}`) of derived errors prints every layer on its own line, with its hints
+/// beneath it.
+pub trait Hints {
+    /// Calls `visit` with every hint of this error: its help first, if it has one, then its notes.
+    fn visit_hints(&self, visit: &mut dyn FnMut(HintKind, &dyn fmt::Display));
+
+    /// Hints of the source of this error, if it has a source which implements this trait.
+    #[doc(hidden)]
+    fn source_hints(&self) -> Option<&dyn Hints> {
+        None
+    }
+
+    /// The help of this error, if it has one.
+    fn help(&self) -> Option<Hint<'_>>
+    where
+        Self: Sized,
+    {
+        let mut found = false;
+        self.visit_hints(&mut |kind, _| found |= kind == HintKind::Help);
+        found.then_some(Hint {
+            hints: self,
+            kind: HintKind::Help,
+            index: 0,
+        })
+    }
+
+    /// The notes of this error.
+    fn notes(&self) -> Notes<'_>
+    where
+        Self: Sized,
+    {
+        let mut count = 0;
+        self.visit_hints(&mut |kind, _| count += usize::from(kind == HintKind::Note));
+        Notes {
+            hints: self,
+            next: 0,
+            count,
+        }
+    }
+}
+
+/// A hint of an error. Formatting it formats the hint with the current state of the error.
+#[derive(Clone, Copy)]
+pub struct Hint<'a> {
+    hints: &'a dyn Hints,
+    kind: HintKind,
+    index: usize,
+}
+
+impl Hint<'_> {
+    /// The kind of this hint.
+    pub fn kind(&self) -> HintKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for Hint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut index = 0;
+        let mut result = Ok(());
+        self.hints.visit_hints(&mut |kind, hint| {
+            if kind == self.kind {
+                if index == self.index {
+                    result = fmt::Display::fmt(hint, f);
+                }
+
+                index += 1;
+            }
+        });
+
+        result
+    }
+}
+
+impl fmt::Debug for Hint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {self}", self.kind)
+    }
+}
+
+/// Iterator over the notes of an error. See [`Hints::notes`].
+pub struct Notes<'a> {
+    hints: &'a dyn Hints,
+    next: usize,
+    count: usize,
+}
+
+impl<'a> Iterator for Notes<'a> {
+    type Item = Hint<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.next < self.count).then(|| {
+            self.next += 1;
+            Hint {
+                hints: self.hints,
+                kind: HintKind::Note,
+                index: self.next - 1,
+            }
+        })
+    }
+}
+
+// Used by the derive to get the hints of sources.
+dyn_ref!(HintsRef, ViaHints, NoHints, hints: Hints);
@@ -0,0 +1,163 @@
+use crate::FieldVisitor;
+use core::error::Error;
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "alloc")]
+use core::fmt::{Debug, Display};
+
+/// Trait for errors which can be turned into HTTP responses.
+///
+/// The derive implements this trait for types with `#[error(status = 404)]`, `type_uri = "..."`
+/// or `title = "..."` arguments. Structs and variants without a status use `500`, except for
+/// `transparent` ones without any of these arguments, which use the status (and the rest of the
+/// problem details) of their source instead, if it implements this trait.
+pub trait HttpError: Error {
+    /// HTTP status code of this error.
+    fn status(&self) -> u16;
+
+    /// URI which identifies the type of this error. `None` is equivalent to `about:blank`.
+    fn type_uri(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Short, human-readable summary of the type of this error.
+    fn title(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Records each extension member of this error with `visitor`. For derived errors, these are
+    /// the fields of the error, recorded like by [`ErrorFields`](crate::ErrorFields).
+    fn visit_extensions(&self, visitor: &mut dyn FieldVisitor) {
+        let _ = visitor;
+    }
+
+    /// The [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807) problem details of this error.
+    #[cfg(feature = "alloc")]
+    fn problem_details(&self) -> ProblemDetails {
+        let mut extensions = Extensions(Vec::new());
+        self.visit_extensions(&mut extensions);
+
+        ProblemDetails {
+            type_uri: self.type_uri().unwrap_or("about:blank"),
+            title: self.title(),
+            status: self.status(),
+            detail: self.to_string(),
+            extensions: extensions.0,
+        }
+    }
+}
+
+// Used by the derive to forward problem details to sources.
+dyn_ref!(HttpRef, ViaHttp, NoHttp, http_error: HttpError);
+
+#[cfg(feature = "alloc")]
+struct Extensions(Vec<(&'static str, ExtensionValue)>);
+
+#[cfg(feature = "alloc")]
+impl FieldVisitor for Extensions {
+    fn record_display(&mut self, name: &'static str, value: &dyn Display) {
+        self.0
+            .push((name, ExtensionValue::String(value.to_string())));
+    }
+
+    fn record_debug(&mut self, name: &'static str, value: &dyn Debug) {
+        self.0
+            .push((name, ExtensionValue::String(format!("{value:?}"))));
+    }
+
+    fn record_i64(&mut self, name: &'static str, value: i64) {
+        self.0.push((name, ExtensionValue::I64(value)));
+    }
+
+    fn record_u64(&mut self, name: &'static str, value: u64) {
+        self.0.push((name, ExtensionValue::U64(value)));
+    }
+
+    fn record_f64(&mut self, name: &'static str, value: f64) {
+        self.0.push((name, ExtensionValue::F64(value)));
+    }
+
+    fn record_bool(&mut self, name: &'static str, value: bool) {
+        self.0.push((name, ExtensionValue::Bool(value)));
+    }
+
+    fn record_str(&mut self, name: &'static str, value: &str) {
+        self.0.push((name, ExtensionValue::String(value.into())));
+    }
+}
+
+/// Value of an extension member of [`ProblemDetails`]. Numbers and booleans keep their type, other
+/// values are stored as strings.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtensionValue {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Bool(bool),
+    String(String),
+}
+
+#[cfg(feature = "alloc")]
+impl From<&str> for ExtensionValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.into())
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl serde::Serialize for ExtensionValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::I64(value) => serializer.serialize_i64(*value),
+            Self::U64(value) => serializer.serialize_u64(*value),
+            Self::F64(value) => serializer.serialize_f64(*value),
+            Self::Bool(value) => serializer.serialize_bool(*value),
+            Self::String(value) => serializer.serialize_str(value),
+        }
+    }
+}
+
+/// [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807) problem details of an error. With the
+/// `serde` feature, this serializes to a JSON object with the `type`, `title`, `status` and
+/// `detail` members, plus one member for each extension.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProblemDetails {
+    /// URI which identifies the type of the problem.
+    pub type_uri: &'static str,
+    /// Short, human-readable summary of the type of the problem.
+    pub title: Option<&'static str>,
+    /// HTTP status code.
+    pub status: u16,
+    /// Human-readable explanation of this occurrence of the problem.
+    pub detail: String,
+    /// Extension members.
+    pub extensions: Vec<(&'static str, ExtensionValue)>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl serde::Serialize for ProblemDetails {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", self.type_uri)?;
+        if let Some(title) = self.title {
+            map.serialize_entry("title", title)?;
+        }
+
+        map.serialize_entry("status", &self.status)?;
+        map.serialize_entry("detail", &self.detail)?;
+        for (name, value) in &self.extensions {
+            map.serialize_entry(name, value)?;
+        }
+
+        map.end()
+    }
+}
@@ -0,0 +1,40 @@
+// Used by the derive to convert transparent errors into `io::Error`s, through autoref-based
+// specialization (see `autoref.rs`): `(&&IoSource::<T>::new()).convert(err, source)` converts the source of `err`,
+// which `source` extracts, if `T: Into<io::Error>`, and wraps `err` itself otherwise.
+
+use core::{error::Error, marker::PhantomData};
+use std::io;
+
+pub struct IoSource<T>(PhantomData<T>);
+
+impl<T> IoSource<T> {
+    #[expect(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub trait ViaIntoIo<T> {
+    fn convert<E>(&self, err: E, source: impl FnOnce(E) -> T) -> io::Error;
+}
+
+impl<T: Into<io::Error>> ViaIntoIo<T> for &IoSource<T> {
+    fn convert<E>(&self, err: E, source: impl FnOnce(E) -> T) -> io::Error {
+        source(err).into()
+    }
+}
+
+pub trait OtherIo<T> {
+    fn convert<E>(&self, err: E, source: impl FnOnce(E) -> T) -> io::Error
+    where
+        E: Error + Send + Sync + 'static;
+}
+
+impl<T> OtherIo<T> for IoSource<T> {
+    fn convert<E>(&self, err: E, _: impl FnOnce(E) -> T) -> io::Error
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        io::Error::other(err)
+    }
+}
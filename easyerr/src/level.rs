@@ -0,0 +1,63 @@
+use core::error::Error;
+
+/// Severity with which an error should be logged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+#[cfg(feature = "log")]
+impl From<Level> for log::Level {
+    fn from(value: Level) -> Self {
+        match value {
+            Level::Error => Self::Error,
+            Level::Warn => Self::Warn,
+            Level::Info => Self::Info,
+            Level::Debug => Self::Debug,
+            Level::Trace => Self::Trace,
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl From<Level> for tracing::Level {
+    fn from(value: Level) -> Self {
+        match value {
+            Level::Error => Self::ERROR,
+            Level::Warn => Self::WARN,
+            Level::Info => Self::INFO,
+            Level::Debug => Self::DEBUG,
+            Level::Trace => Self::TRACE,
+        }
+    }
+}
+
+/// Trait for errors which can be logged.
+///
+/// The derive implements this trait for every type. Structs and variants have the
+/// [`Error`](Level::Error) level unless they have a `#[error(level = warn)]` argument, and are
+/// logged with their fields as structured key/values. `transparent` ones are logged as their
+/// source if it implements this trait, with its level unless they have a level of their own.
+pub trait LogError: Error {
+    /// Default level of this error.
+    fn level(&self) -> Level {
+        Level::Error
+    }
+
+    /// Logs this error and its sources at `level`, through `log` and/or `tracing`, depending on
+    /// which of these features are enabled.
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    fn log(&self, level: Level) {
+        #[cfg(feature = "log")]
+        crate::__private::log_error(&self, level, core::module_path!(), &[]);
+        #[cfg(feature = "tracing")]
+        crate::__private::trace_error!(level, self);
+    }
+}
+
+// Used by the derive to forward levels and logging to sources.
+dyn_ref!(LogRef, ViaLog, NoLog, log_error: LogError);
@@ -1,27 +1,95 @@
 #![doc = include_str!(concat!("../", core::env!("CARGO_PKG_README")))]
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+mod attach;
+#[macro_use]
+mod autoref;
+#[cfg(feature = "catalog")]
+pub mod catalog;
+#[cfg(feature = "alloc")]
+mod describe;
+#[cfg(feature = "miette")]
+mod diagnostics;
+mod exit;
+#[cfg(feature = "std")]
+pub mod ffi;
+mod field;
+mod hints;
+mod http;
+#[cfg(feature = "std")]
+mod io;
+mod level;
+mod raw;
+mod redact;
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "alloc")]
+mod snapshot;
+
+#[cfg(feature = "alloc")]
+pub use attach::Attached;
+#[cfg(feature = "alloc")]
+pub use describe::{Described, DescribedCtx};
 pub use easyerr_derive::Error;
+#[cfg(feature = "std")]
+pub use exit::Exit;
+pub use exit::ExitCodeError;
+pub use field::{ErrorFields, FieldVisitor};
+pub use hints::{Hint, HintKind, Hints, Notes};
+pub use http::HttpError;
+#[cfg(feature = "alloc")]
+pub use http::{ExtensionValue, ProblemDetails};
+pub use level::{Level, LogError};
+pub use raw::RawError;
+#[cfg(any(feature = "std", target_has_atomic = "ptr"))]
+pub use redact::unredacted;
+pub use redact::{is_unredacted, Redacted, Redaction};
+#[cfg(feature = "serde")]
+pub use ser::SerializableChain;
+#[cfg(feature = "alloc")]
+pub use snapshot::ErrorSnapshot;
 
-/// Prelude. Currently, just reexports everything (a grand total of... 4 items), but might change
-/// in the future.
+/// Prelude, with the derive, the traits needed to add context to errors and [`ensure!`].
 pub mod prelude {
-    pub use crate::*;
+    pub use crate::{ensure, Error, ErrorContext, ResultExt};
 }
 
-/// Trait for types which can add context to some [`Error`] ([`Source`](Self::Source)), transforming
-/// it into a new [`Error`] ([`Err`](Self::Err)).
+/// Trait for types which can add context to some error `Source`, transforming it into a new
+/// [`Error`] ([`Err`](Self::Err)).
+///
+/// The source is usually an [`Error`], but can also be an error report type like `anyhow::Error`
+/// or `eyre::Report` (with the `anyhow` and `eyre` features). A context can accept more than one
+/// type of source, e.g. contexts of boxed sources accept any error which can be boxed.
 ///
 /// [`Error`]: core::error::Error
-pub trait ErrorContext {
-    /// The source error type which this context can be added to.
-    type Source: core::error::Error;
+pub trait ErrorContext<Source> {
     /// The new error type after adding this context.
     type Err: core::error::Error;
 
-    /// Add this context to the given error [`Source`](Self::Source), transforming it into
-    /// [`Err`](Self::Err).
-    fn add_to_source(self, source: Self::Source) -> Self::Err;
+    /// Add this context to the given error `source`, transforming it into [`Err`](Self::Err).
+    fn add_to_source(self, source: Source) -> Self::Err;
+}
+
+/// Trait for errors with stable, greppable error codes.
+///
+/// The derive implements this trait for types with `#[error(code = "...")]` arguments. When a type
+/// has codes, every struct or variant needs one, except for `transparent` ones, which use the
+/// code of their source instead. A `transparent` variant whose source doesn't implement this trait
+/// can be given a code of its own. Adding `#[error(show_code)]` to the type prefixes its messages
+/// with `[code] `.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` doesn't have an error code",
+    note = "give `transparent` variants with this source a `code` of their own"
+)]
+pub trait ErrorCode {
+    /// The error code of this error.
+    fn code(&self) -> &'static str;
 }
 
 /// Extension trait for [`Result`] which adds useful methods to use [`ErrorContext`]s.
@@ -29,34 +97,372 @@ pub trait ResultExt<T, E1> {
     /// Add the given context to the error of this result.
     fn context<C, E2>(self, ctx: C) -> Result<T, E2>
     where
-        C: ErrorContext<Err = E2, Source = E1>;
+        C: ErrorContext<E1, Err = E2>;
 
     /// Add the given context to the error of this result, lazily.
     fn with_context<C, E2, F>(self, f: F) -> Result<T, E2>
     where
         F: FnOnce(&E1) -> C,
-        C: ErrorContext<Err = E2, Source = E1>;
+        C: ErrorContext<E1, Err = E2>;
+
+    /// Attach `value` to the error of this result. See [`Attached`].
+    #[cfg(feature = "alloc")]
+    fn attach<A>(self, value: A) -> Result<T, Attached<E1>>
+    where
+        A: core::fmt::Display + Send + Sync + 'static;
+
+    /// Attach a value to the error of this result, lazily. See [`Attached`].
+    #[cfg(feature = "alloc")]
+    fn attach_with<A, F>(self, f: F) -> Result<T, Attached<E1>>
+    where
+        A: core::fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> A;
+
+    /// Describe the error of this result with an ad-hoc message. See [`Described`].
+    #[cfg(feature = "alloc")]
+    fn describe(
+        self,
+        message: impl Into<alloc::borrow::Cow<'static, str>>,
+    ) -> Result<T, Described<E1>>
+    where
+        E1: core::error::Error + 'static;
+
+    /// Describe the error of this result with an ad-hoc message, lazily. See [`Described`].
+    #[cfg(feature = "alloc")]
+    fn describe_with<M, F>(self, f: F) -> Result<T, Described<E1>>
+    where
+        E1: core::error::Error + 'static,
+        M: Into<alloc::borrow::Cow<'static, str>>,
+        F: FnOnce() -> M;
+
+    /// Logs the error of this result at its default [level](LogError::level), if any.
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    fn log_err(self) -> Result<T, E1>
+    where
+        E1: LogError;
+
+    /// Logs the error of this result at the given level, if any.
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    fn inspect_err_log(self, level: Level) -> Result<T, E1>
+    where
+        E1: LogError;
+}
+
+/// Outlined error path of [`ResultExt::context`], so that building the error stays out of the
+/// happy path of its callers.
+#[cold]
+#[inline(never)]
+fn add_context<C: ErrorContext<E>, E>(ctx: C, source: E) -> C::Err {
+    ctx.add_to_source(source)
+}
+
+/// Outlined error path of [`ResultExt::with_context`].
+#[cold]
+#[inline(never)]
+fn add_context_with<C: ErrorContext<E>, E, F: FnOnce(&E) -> C>(f: F, source: E) -> C::Err {
+    f(&source).add_to_source(source)
 }
 
 impl<T, E1> ResultExt<T, E1> for Result<T, E1> {
-    #[inline(always)]
+    #[inline]
     fn context<C, E2>(self, ctx: C) -> Result<T, E2>
     where
-        C: ErrorContext<Err = E2, Source = E1>,
+        C: ErrorContext<E1, Err = E2>,
     {
-        self.map_err(|e| ctx.add_to_source(e))
+        match self {
+            Ok(value) => Ok(value),
+            Err(e) => Err(add_context(ctx, e)),
+        }
     }
 
-    #[inline(always)]
+    #[inline]
     fn with_context<C, E2, F>(self, f: F) -> Result<T, E2>
     where
         F: FnOnce(&E1) -> C,
-        C: ErrorContext<Err = E2, Source = E1>,
+        C: ErrorContext<E1, Err = E2>,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(e) => Err(add_context_with(f, e)),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn attach<A>(self, value: A) -> Result<T, Attached<E1>>
+    where
+        A: core::fmt::Display + Send + Sync + 'static,
+    {
+        self.map_err(|e| Attached::new(e, value))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn attach_with<A, F>(self, f: F) -> Result<T, Attached<E1>>
+    where
+        A: core::fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> A,
+    {
+        self.map_err(|e| Attached::new(e, f()))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn describe(
+        self,
+        message: impl Into<alloc::borrow::Cow<'static, str>>,
+    ) -> Result<T, Described<E1>>
+    where
+        E1: core::error::Error + 'static,
+    {
+        self.context(DescribedCtx::new(message))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn describe_with<M, F>(self, f: F) -> Result<T, Described<E1>>
+    where
+        E1: core::error::Error + 'static,
+        M: Into<alloc::borrow::Cow<'static, str>>,
+        F: FnOnce() -> M,
     {
-        self.map_err(|e| f(&e).add_to_source(e))
+        self.map_err(|e| Described::new(f(), e))
+    }
+
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    fn log_err(self) -> Result<T, E1>
+    where
+        E1: LogError,
+    {
+        self.inspect_err(|e| e.log(e.level()))
+    }
+
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    fn inspect_err_log(self, level: Level) -> Result<T, E1>
+    where
+        E1: LogError,
+    {
+        self.inspect_err(|e| e.log(level))
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use core::{error::Error, fmt};
+
+    #[cfg(feature = "alloc")]
+    pub use alloc::boxed::Box;
+    #[cfg(feature = "defmt")]
+    pub use defmt;
+    #[cfg(feature = "catalog")]
+    pub use inventory;
+    #[cfg(feature = "tracing")]
+    pub use {crate::__trace_error as trace_error, tracing};
+    #[cfg(feature = "miette")]
+    pub use {
+        crate::diagnostics::{DiagnosticRef, NoDiagnostic, ViaDiagnostic},
+        alloc::format,
+        miette,
+    };
+
+    #[cfg(feature = "std")]
+    pub use crate::io::{IoSource, OtherIo, ViaIntoIo};
+    pub use crate::{
+        exit::{ExitCodeRef, NoExitCode, ViaExitCode},
+        field::{Field, FieldRef, Opaque, ViaDebug, ViaDisplay, ViaPrimitive, ViaPrintable},
+        hints::{HintsRef, NoHints, ViaHints},
+        http::{HttpRef, NoHttp, ViaHttp},
+        level::{LogRef, NoLog, ViaLog},
+    };
+    #[cfg(feature = "serde")]
+    pub use {
+        crate::ser::{serialize_error, serialize_redacted, SerializeFields},
+        serde,
+    };
+
+    /// OS error code of the first [`io::Error`](std::io::Error) in the chain starting at `err`.
+    /// Used by the `RawError` impl of derived errors.
+    #[cfg(feature = "std")]
+    pub fn raw_os_error(err: &(dyn Error + 'static)) -> Option<i32> {
+        let mut next = Some(err);
+        while let Some(cause) = next {
+            if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+                return io.raw_os_error();
+            }
+
+            next = cause.source();
+        }
+
+        None
+    }
+
+    /// Without the `std` feature, there are no `io::Error`s to get an OS error code from.
+    #[cfg(not(feature = "std"))]
+    pub fn raw_os_error(_: &(dyn Error + 'static)) -> Option<i32> {
+        None
+    }
+
+    /// Logs `err` at `level` through `log`, with the given structured `fields`. Used by the
+    /// `LogError` impl of derived errors.
+    #[cfg(feature = "log")]
+    pub fn log_error(
+        err: &dyn Error,
+        level: crate::Level,
+        target: &str,
+        fields: &[(&'static str, &dyn fmt::Display)],
+    ) {
+        struct Fields<'a>(&'a [(&'static str, &'a dyn fmt::Display)]);
+
+        impl log::kv::Source for Fields<'_> {
+            fn visit<'kvs>(
+                &'kvs self,
+                visitor: &mut dyn log::kv::VisitSource<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                for &(key, value) in self.0 {
+                    visitor.visit_pair(key.into(), log::kv::Value::from_dyn_display(value))?;
+                }
+
+                Ok(())
+            }
+        }
+
+        let level = log::Level::from(level);
+        if level <= log::max_level() {
+            log::logger().log(
+                &log::Record::builder()
+                    .args(format_args!("{err:#}"))
+                    .level(level)
+                    .target(target)
+                    .key_values(&Fields(fields))
+                    .build(),
+            );
+        }
+    }
+
+    /// Displays by calling a closure with the formatter. Used by the derive for hints and for the
+    /// help and url of diagnostics, so that redacted fields in them are revealed in full mode
+    /// (`{:+}`) like in messages.
+    pub struct DisplayFn<F>(pub F);
+
+    impl<F: Fn(&mut fmt::Formatter<'_>) -> fmt::Result> fmt::Display for DisplayFn<F> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            (self.0)(f)
+        }
+    }
+
+    /// Writes `value` without any of the flags of `f`, except for full mode (`{:+}`).
+    pub fn fmt_message(f: &mut fmt::Formatter<'_>, value: &dyn fmt::Display) -> fmt::Result {
+        if f.sign_plus() {
+            write!(f, "{value:+}")
+        } else {
+            write!(f, "{value}")
+        }
+    }
+
+    /// Writes `: cause` for every error in the chain starting at `source`. Used by the alternate
+    /// (`{:#}`) display of derived errors.
+    pub fn fmt_chain(
+        f: &mut fmt::Formatter<'_>,
+        source: Option<&(dyn Error + 'static)>,
+    ) -> fmt::Result {
+        let mut next = source;
+        while let Some(cause) = next {
+            f.write_str(": ")?;
+            fmt_message(f, cause)?;
+            next = cause.source();
+        }
+
+        Ok(())
+    }
+
+    /// Writes the chain starting at `source`, like [`fmt_chain`], for an error with the given
+    /// `hints`. If any layer of the chain has hints, every layer is written on its own line instead,
+    /// followed by its hints. Used by the alternate (`{:#}`) display of derived errors.
+    pub fn fmt_hints(
+        f: &mut fmt::Formatter<'_>,
+        hints: &dyn crate::Hints,
+        source: Option<&(dyn Error + 'static)>,
+    ) -> fmt::Result {
+        let mut any = false;
+        let mut next = Some(hints);
+        while let Some(hints) = next.filter(|_| !any) {
+            hints.visit_hints(&mut |_, _| any = true);
+            next = hints.source_hints();
+        }
+
+        if !any {
+            return fmt_chain(f, source);
+        }
+
+        let mut hints = Some(hints);
+        let mut next = source;
+        loop {
+            if let Some(layer) = hints {
+                let mut result = Ok(());
+                layer.visit_hints(&mut |kind, hint| {
+                    if result.is_ok() {
+                        result = write!(f, "\n{kind}: ").and_then(|()| fmt_message(f, hint));
+                    }
+                });
+
+                result?;
+                hints = layer.source_hints();
+            }
+
+            let Some(cause) = next else {
+                return Ok(());
+            };
+
+            f.write_str("\ncaused by: ")?;
+            fmt_message(f, cause)?;
+            next = cause.source();
+        }
+    }
+
+    /// Writes the message of `err` followed by a list of its sources. Used by the `Debug` impl of
+    /// derived errors with `#[error(debug = "report")]`.
+    pub fn fmt_report(f: &mut fmt::Formatter<'_>, err: &dyn Error) -> fmt::Result {
+        fmt_message(f, err)?;
+
+        let mut next = err.source();
+        if next.is_some() {
+            f.write_str("\n\nCaused by:")?;
+        }
+
+        let mut index = 0;
+        while let Some(cause) = next {
+            write!(f, "\n    {index}: ")?;
+            fmt_message(f, cause)?;
+
+            index += 1;
+            next = cause.source();
+        }
+
+        Ok(())
     }
 }
 
+/// Emits a `tracing` event for `err` at `level` (an [`easyerr::Level`](crate::Level)), with the
+/// given structured fields. Used by the `LogError` impl of derived errors.
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __trace_error {
+    (@event $level:ident, $err:expr $(, $key:literal = $value:expr)*) => {
+        $crate::__private::tracing::event!(
+            $crate::__private::tracing::Level::$level,
+            $($key = %$value,)*
+            "{:#}",
+            $err
+        )
+    };
+    ($level:expr, $err:expr $(, $key:literal = $value:expr)* $(,)?) => {
+        match $level {
+            $crate::Level::Error => $crate::__trace_error!(@event ERROR, $err $(, $key = $value)*),
+            $crate::Level::Warn => $crate::__trace_error!(@event WARN, $err $(, $key = $value)*),
+            $crate::Level::Info => $crate::__trace_error!(@event INFO, $err $(, $key = $value)*),
+            $crate::Level::Debug => $crate::__trace_error!(@event DEBUG, $err $(, $key = $value)*),
+            $crate::Level::Trace => $crate::__trace_error!(@event TRACE, $err $(, $key = $value)*),
+        }
+    };
+}
+
 /// Macro that evaluates an expression and returns an error if it is not true.
 #[macro_export]
 macro_rules! ensure {
@@ -0,0 +1,27 @@
+use core::error::Error;
+
+/// Trait for errors with stable `i32` codes, such as the errors of a C ABI.
+///
+/// The derive implements this trait for types with `#[error(errno = -2)]` arguments or explicit
+/// discriminants. Every struct or variant must then have a raw code, except for `transparent`
+/// ones, which use the raw code of their source.
+pub trait RawError: Error {
+    /// Raw code of this error.
+    fn as_raw(&self) -> i32;
+
+    /// Reconstructs an error from its raw code. Only errors without fields can be reconstructed,
+    /// so this returns `None` for any other code.
+    fn from_raw(raw: i32) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let _ = raw;
+        None
+    }
+
+    /// OS error code of the [`io::Error`](std::io::Error) which caused this error, if any. Only
+    /// available with the `std` feature - otherwise, this is always `None`.
+    fn raw_os_error(&self) -> Option<i32> {
+        None
+    }
+}
@@ -0,0 +1,173 @@
+use core::fmt::{self, Write};
+
+// Depth of nested `unredacted` scopes. With `std`, it is tracked per thread, so that a scope only
+// reveals values formatted on its own thread. Without it, it is a global counter, which requires
+// atomic read-modify-write operations: on targets without them (e.g. thumbv6m), there are no
+// `unredacted` scopes at all.
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static UNREDACTED: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+#[cfg(all(not(feature = "std"), target_has_atomic = "ptr"))]
+static UNREDACTED: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+#[cfg(any(feature = "std", target_has_atomic = "ptr"))]
+fn set_unredacted(enter: bool) {
+    #[cfg(feature = "std")]
+    UNREDACTED.with(|depth| {
+        depth.set(if enter {
+            depth.get() + 1
+        } else {
+            depth.get() - 1
+        });
+    });
+
+    #[cfg(not(feature = "std"))]
+    if enter {
+        UNREDACTED.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+    } else {
+        UNREDACTED.fetch_sub(1, core::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+/// Whether the current code is running inside an [`unredacted`] scope. Always `false` on targets
+/// where `unredacted` isn't available.
+pub fn is_unredacted() -> bool {
+    #[cfg(feature = "std")]
+    return UNREDACTED.with(|depth| depth.get() > 0);
+
+    #[cfg(all(not(feature = "std"), target_has_atomic = "ptr"))]
+    return UNREDACTED.load(core::sync::atomic::Ordering::Relaxed) > 0;
+
+    #[cfg(not(any(feature = "std", target_has_atomic = "ptr")))]
+    return false;
+}
+
+/// Runs `f` with redaction disabled, i.e. fields marked with `#[error(redact)]` are displayed as
+/// usual while `f` runs.
+///
+/// With the `std` feature this only affects the current thread. Without it, it affects every
+/// thread, so values formatted concurrently on other threads are revealed too. It is unavailable
+/// without `std` on targets without atomic read-modify-write operations.
+#[cfg(any(feature = "std", target_has_atomic = "ptr"))]
+pub fn unredacted<R>(f: impl FnOnce() -> R) -> R {
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            set_unredacted(false);
+        }
+    }
+
+    set_unredacted(true);
+    let _guard = Guard;
+    f()
+}
+
+/// How a redacted value is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redaction {
+    /// Rendered as `<redacted>`.
+    Hide,
+    /// Rendered as `<redacted:hash>`, where `hash` is a hash of the `Debug` output of the value,
+    /// which allows correlating values without revealing them. The hash is the same whether the
+    /// value is displayed or debug-formatted.
+    ///
+    /// The hash is an unkeyed, 32-bit FNV-1a hash. It is **not** cryptographic: low-entropy values
+    /// such as PINs, short tokens or email addresses can be recovered by hashing every candidate,
+    /// so only use it for values which are hard to guess.
+    Hash,
+}
+
+/// 32-bit FNV-1a hasher which hashes whatever is written to it.
+struct Fnv(u32);
+
+impl Write for Fnv {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            self.0 ^= u32::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0193);
+        }
+
+        Ok(())
+    }
+}
+
+/// Wrapper around a value which hides it when formatted, unless the formatter it was created with
+/// is in full mode (`{:+}`) or it is formatted inside an [`unredacted`] scope.
+///
+/// This is used by the derive for fields marked with `#[error(redact)]` or
+/// `#[error(redact = "hash")]`.
+pub struct Redacted<'a, T: ?Sized> {
+    value: &'a T,
+    mode: Redaction,
+    reveal: bool,
+}
+
+impl<'a, T: ?Sized> Redacted<'a, T> {
+    /// Wraps `value`, deciding whether to reveal it based on the formatter `f`.
+    pub fn new(value: &'a T, mode: Redaction, f: &fmt::Formatter<'_>) -> Self {
+        Self {
+            value,
+            mode,
+            reveal: f.sign_plus() || is_unredacted(),
+        }
+    }
+
+    /// Wraps `value`, which is only revealed if it is wrapped inside an [`unredacted`] scope. This
+    /// is used where there is no formatter to decide with, e.g. for structured fields.
+    pub fn scoped(value: &'a T, mode: Redaction) -> Self {
+        Self {
+            value,
+            mode,
+            reveal: is_unredacted(),
+        }
+    }
+
+    /// Wraps `value`, which is always redacted regardless of how it is formatted.
+    pub fn hidden(value: &'a T, mode: Redaction) -> Self {
+        Self {
+            value,
+            mode,
+            reveal: false,
+        }
+    }
+
+    fn fmt_redacted(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        write: impl FnOnce(&mut Fnv) -> fmt::Result,
+    ) -> fmt::Result {
+        match self.mode {
+            Redaction::Hide => f.write_str("<redacted>"),
+            Redaction::Hash => {
+                let mut hasher = Fnv(0x811c_9dc5);
+                write(&mut hasher)?;
+                write!(f, "<redacted:{:08x}>", hasher.0)
+            }
+        }
+    }
+}
+
+impl<T: fmt::Display + fmt::Debug + ?Sized> fmt::Display for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.reveal {
+            fmt::Display::fmt(self.value, f)
+        } else {
+            // hashed like in `Debug`, so that both give the same hash
+            self.fmt_redacted(f, |h| write!(h, "{:?}", self.value))
+        }
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.reveal {
+            fmt::Debug::fmt(self.value, f)
+        } else {
+            self.fmt_redacted(f, |h| write!(h, "{:?}", self.value))
+        }
+    }
+}
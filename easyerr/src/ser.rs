@@ -0,0 +1,81 @@
+use crate::{field::Field, Redacted, Redaction};
+use core::{error::Error, fmt};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+/// Serializes the output of a [`Display`](fmt::Display) value as a string.
+struct DisplayStr<'a>(&'a dyn fmt::Display);
+
+impl Serialize for DisplayStr<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self.0)
+    }
+}
+
+/// Adapter which serializes any [`Error`] and its chain of sources as
+/// `{ "message": ..., "source": { "message": ..., "source": ... } }`.
+///
+/// Derived errors implement `Serialize` themselves when the `serde` feature is enabled, but their
+/// sources are serialized with this adapter.
+#[derive(Clone, Copy)]
+pub struct SerializableChain<'a>(pub &'a (dyn Error + 'a));
+
+impl Serialize for SerializableChain<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("message", &DisplayStr(self.0))?;
+        map.serialize_entry("source", &self.0.source().map(SerializableChain))?;
+        map.end()
+    }
+}
+
+/// Serializes the fields of a derived error into a map. Implemented by the derive.
+#[doc(hidden)]
+pub trait SerializeFields {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error>;
+}
+
+struct Fields<'a, T: ?Sized>(&'a T);
+
+impl<T: SerializeFields + ?Sized> Serialize for Fields<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        self.0.serialize_fields(&mut map)?;
+        map.end()
+    }
+}
+
+#[doc(hidden)]
+pub fn serialize_error<S, E>(serializer: S, kind: &str, err: &E) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    E: Error + SerializeFields,
+{
+    let mut map = serializer.serialize_map(Some(4))?;
+    map.serialize_entry("kind", kind)?;
+    map.serialize_entry("message", &DisplayStr(err))?;
+    map.serialize_entry("fields", &Fields(err))?;
+    map.serialize_entry("source", &err.source().map(SerializableChain))?;
+    map.end()
+}
+
+/// Serializes the redacted field `value`, which is recorded as `field`. While redacted, the field is
+/// hashed from the `Debug` rendering of `field`, so that hashes match the ones in the messages and
+/// the `Debug` output of the error.
+#[doc(hidden)]
+pub fn serialize_redacted<M, T>(
+    map: &mut M,
+    key: &str,
+    value: &T,
+    field: &Field<'_>,
+    mode: Redaction,
+) -> Result<(), M::Error>
+where
+    M: SerializeMap,
+    T: Serialize + ?Sized,
+{
+    if crate::is_unredacted() {
+        map.serialize_entry(key, value)
+    } else {
+        map.serialize_entry(key, &DisplayStr(&Redacted::hidden(field, mode)))
+    }
+}
@@ -0,0 +1,85 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+use core::{any::type_name, error::Error, fmt};
+
+/// Owned snapshot of an [`Error`] and its chain of sources.
+///
+/// Captures the rendered message, the type name and, recursively, the sources of an error into a
+/// value which is `Clone + Send + Sync` and implements [`Error`] itself. Useful to share errors
+/// which are not `Clone` (e.g. `std::io::Error`) between tasks, to cache them or, with the `serde`
+/// feature, to send them to another process.
+///
+/// The type name is only known for the outermost error. Sources which are themselves snapshots
+/// are kept as they are.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorSnapshot {
+    type_name: Option<String>,
+    message: String,
+    source: Option<Box<ErrorSnapshot>>,
+}
+
+impl ErrorSnapshot {
+    /// Takes a snapshot of `err` and its chain of sources.
+    pub fn new<E: Error + 'static>(err: &E) -> Self {
+        let err: &(dyn Error + 'static) = err;
+        if let Some(snapshot) = err.downcast_ref::<Self>() {
+            return snapshot.clone();
+        }
+
+        Self {
+            type_name: Some(type_name::<E>().to_string()),
+            ..Self::from_dyn(err)
+        }
+    }
+
+    /// Takes a snapshot of `err` and its chain of sources. Since the concrete type of `err` is
+    /// unknown, the snapshot has no type name unless `err` is a snapshot itself.
+    pub fn from_dyn(err: &(dyn Error + 'static)) -> Self {
+        if let Some(snapshot) = err.downcast_ref::<Self>() {
+            return snapshot.clone();
+        }
+
+        Self {
+            type_name: None,
+            message: err.to_string(),
+            source: err.source().map(|s| Box::new(Self::from_dyn(s))),
+        }
+    }
+
+    /// Type name of the error this snapshot was taken of, if known.
+    pub fn type_name(&self) -> Option<&str> {
+        self.type_name.as_deref()
+    }
+
+    /// Message of the error this snapshot was taken of.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Snapshot of the source of the error this snapshot was taken of.
+    pub fn snapshot_source(&self) -> Option<&Self> {
+        self.source.as_deref()
+    }
+}
+
+/// Displays the message of the snapshot. Like derived errors, the alternate flag (`{:#}`) also
+/// displays the chain of sources.
+impl fmt::Display for ErrorSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        if f.alternate() {
+            crate::__private::fmt_chain(f, self.source())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for ErrorSnapshot {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|s| s as &(dyn Error + 'static))
+    }
+}
@@ -0,0 +1,96 @@
+#![cfg(all(feature = "anyhow", feature = "std"))]
+
+use core::error::Error as _;
+
+use easyerr::prelude::*;
+
+#[derive(Debug, Error)]
+#[error("missing key {key:?}")]
+struct MissingKeyError {
+    key: &'static str,
+}
+
+#[derive(Debug, Error)]
+enum PluginError {
+    #[error("plugin {name} failed")]
+    Failed { source: anyhow::Error, name: String },
+    #[error(transparent)]
+    Other { source: anyhow::Error },
+}
+
+#[derive(Debug, Error)]
+#[error("failed to load config")]
+struct ConfigError {
+    source: anyhow::Error,
+}
+
+type AnyError = anyhow::Error;
+
+#[derive(Debug, Error)]
+#[error("failed to start")]
+struct StartError {
+    #[error(source(report))]
+    source: AnyError,
+}
+
+fn run_plugin() -> anyhow::Result<()> {
+    Err(anyhow::Error::new(MissingKeyError { key: "name" }).context("reading manifest"))
+}
+
+#[test]
+fn anyhow_source() {
+    let err = run_plugin()
+        .context(PluginCtx::Failed { name: "fmt".into() })
+        .unwrap_err();
+    assert_eq!(
+        format!("{err:#}"),
+        "plugin fmt failed: reading manifest: missing key \"name\""
+    );
+
+    let err = run_plugin().context(PluginCtx::Other).unwrap_err();
+    assert_eq!(err.to_string(), "reading manifest");
+    assert_eq!(err.source().unwrap().to_string(), "missing key \"name\"");
+
+    let err = run_plugin().context(ConfigCtx {}).unwrap_err();
+    assert_eq!(
+        format!("{err:#}"),
+        "failed to load config: reading manifest: missing key \"name\""
+    );
+
+    let err = run_plugin().context(StartCtx {}).unwrap_err();
+    assert_eq!(
+        format!("{err:#}"),
+        "failed to start: reading manifest: missing key \"name\""
+    );
+}
+
+#[test]
+fn into_anyhow() {
+    let err: anyhow::Error = run_plugin().context(ConfigCtx {}).unwrap_err().into();
+    let chain = err.chain().map(|e| e.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        chain,
+        [
+            "failed to load config",
+            "reading manifest",
+            "missing key \"name\""
+        ]
+    );
+    assert!(err.downcast_ref::<ConfigError>().is_some());
+    assert!(err.root_cause().is::<MissingKeyError>());
+}
+
+#[test]
+fn source_backtrace() {
+    let source = run_plugin().unwrap_err();
+    let backtrace: *const std::backtrace::Backtrace = source.backtrace();
+    let rendered = source.backtrace().to_string();
+
+    let err: anyhow::Error = Err::<(), _>(source)
+        .context(ConfigCtx {})
+        .unwrap_err()
+        .into();
+    let source = &err.downcast_ref::<ConfigError>().unwrap().source;
+    assert!(core::ptr::eq(source.backtrace(), backtrace));
+    assert_eq!(source.backtrace().to_string(), rendered);
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "alloc")]
+
+use core::error::Error as _;
+
+use easyerr::prelude::*;
+use easyerr::Attached;
+
+#[derive(Debug, Error)]
+#[error("invalid digit {digit:?}")]
+struct ParseError {
+    digit: char,
+}
+
+#[derive(Debug, PartialEq)]
+struct Row(usize);
+
+impl core::fmt::Display for Row {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "in row {}", self.0)
+    }
+}
+
+fn parse(row: usize) -> Result<u32, Attached<ParseError>> {
+    Err(ParseError { digit: 'x' })
+        .attach(Row(row))
+        .map_err(|e| e.attach_with(|| "in file data.csv"))
+}
+
+#[test]
+fn display() {
+    let err = parse(17).unwrap_err();
+    assert_eq!(err.to_string(), "in row 17, in file data.csv");
+    assert_eq!(
+        format!("{err:#}"),
+        "in row 17, in file data.csv: invalid digit 'x'"
+    );
+    assert_eq!(err.source().unwrap().to_string(), "invalid digit 'x'");
+}
+
+#[test]
+fn request_ref() {
+    let err = parse(17).unwrap_err().attach(Row(18));
+    assert_eq!(err.request_ref::<Row>(), Some(&Row(18)));
+    assert_eq!(err.request_ref::<&str>(), Some(&"in file data.csv"));
+    assert_eq!(err.request_ref::<u32>(), None);
+    assert_eq!(err.attachments().count(), 3);
+    assert_eq!(err.into_error().digit, 'x');
+}
+
+#[test]
+fn debug() {
+    let err = parse(1).unwrap_err();
+    assert_eq!(
+        format!("{err:?}"),
+        "Attached { error: ParseError { digit: 'x' }, attachments: [in row 1, in file data.csv] }"
+    );
+}
+
+#[derive(Debug, Error)]
+#[error("failed to load data")]
+struct LoadError {
+    source: Attached<ParseError>,
+}
+
+#[test]
+fn as_source() {
+    let err = parse(2).context(LoadCtx {}).unwrap_err();
+    assert_eq!(
+        format!("{err:#}"),
+        "failed to load data: in row 2, in file data.csv: invalid digit 'x'"
+    );
+}
+
+#[test]
+fn nested() {
+    let err = parse(3).attach("in batch 2").unwrap_err();
+    assert_eq!(err.to_string(), "in batch 2");
+    assert_eq!(
+        format!("{err:#}"),
+        "in batch 2: in row 3, in file data.csv: invalid digit 'x'"
+    );
+    assert_eq!(err.request_ref::<Row>(), None);
+    assert_eq!(err.error().request_ref::<Row>(), Some(&Row(3)));
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "catalog")]
+
+use easyerr::{catalog, Error};
+
+/// The configuration could not be loaded.
+///
+/// Check that the file exists.
+#[derive(Debug, Error)]
+#[error("failed to load config {path}", code = "E0100")]
+struct LoadConfigError {
+    path: String,
+}
+
+#[derive(Debug, Error)]
+enum ServeError {
+    /// The port is already in use by another process.
+    #[error("port {f0} is in use", code = "E0200")]
+    PortInUse(u16),
+    #[error(transparent, code = "E0201")]
+    Config { source: LoadConfigError },
+}
+
+#[test]
+fn registers_entries() {
+    let entries = catalog::entries();
+    assert_eq!(entries.len(), 3);
+
+    let e = catalog::find("LoadConfigError").unwrap();
+    assert_eq!(e.variant, None);
+    assert_eq!(e.message, Some("failed to load config {path}"));
+    assert_eq!(
+        e.docs,
+        "The configuration could not be loaded.\n\nCheck that the file exists."
+    );
+    assert_eq!(e.code, Some("E0100"));
+
+    let e = catalog::find("ServeError::PortInUse").unwrap();
+    assert_eq!(e.docs, "The port is already in use by another process.");
+    assert_eq!(e.module, "catalog");
+
+    let e = catalog::find("E0201").unwrap();
+    assert_eq!(e.path().to_string(), "ServeError::Config");
+    assert_eq!(e.message, None);
+
+    assert!(catalog::find("catalog::ServeError::Config").is_some());
+    assert!(catalog::find("ServeError::Missing").is_none());
+    assert!(catalog::find("Config").is_none());
+}
+
+#[test]
+fn dumps() {
+    let markdown = catalog::Markdown.to_string();
+    assert!(markdown.starts_with("# Errors\n"));
+    assert!(markdown.contains("## `ServeError::PortInUse` (E0200)"));
+
+    let json = catalog::Json.to_string();
+    assert!(json.starts_with("[{\"module\":\"catalog\",\"type\":\"LoadConfigError\""));
+    assert!(json.contains("\"message\":\"port {f0} is in use\""));
+    assert!(json.contains("\"docs\":\"The configuration could not be loaded.\\n\\nCheck"));
+}
+
+#[test]
+fn unused_errors_are_still_constructible() {
+    let e = ServeError::Config {
+        source: LoadConfigError { path: "a".into() },
+    };
+    assert_eq!(e.to_string(), "failed to load config a");
+    assert_eq!(ServeError::PortInUse(80).to_string(), "port 80 is in use");
+}
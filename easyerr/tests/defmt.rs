@@ -0,0 +1,66 @@
+#![cfg(feature = "defmt")]
+
+use easyerr::prelude::*;
+
+#[derive(Debug, Error)]
+#[error(
+    "buffer of {len} bytes is too small, need {needed:#x}",
+    code = "S0",
+    defmt
+)]
+struct BufferError {
+    len: usize,
+    needed: u32,
+}
+
+#[derive(Debug, Error)]
+#[error(defmt, show_code)]
+enum SensorError {
+    #[error("sensor {f0} timed out", code = "S1")]
+    Timeout(u8),
+    #[error("bad key {key} {{escaped}}", code = "S2")]
+    BadKey {
+        #[error(redact)]
+        key: u32,
+    },
+    #[error("sensor {} misread {:?} (again: {0})", code = "S3")]
+    Misread(u8, u16),
+    #[error(transparent)]
+    Buffer { source: BufferError },
+    #[error(transparent, fallback = "no buffer", code = "S4")]
+    MaybeBuffer { source: Option<BufferError> },
+}
+
+// formatting with defmt needs a target and a decoder, so this only checks that the derive's
+// format strings are accepted by defmt and that the impls exist.
+fn assert_format<T: defmt::Format>() {}
+
+#[test]
+fn format() {
+    assert_format::<BufferError>();
+    assert_format::<SensorError>();
+
+    assert_eq!(
+        SensorError::Timeout(2).to_string(),
+        "[S1] sensor 2 timed out"
+    );
+    assert_eq!(
+        SensorError::BadKey { key: 7 }.to_string(),
+        "[S2] bad key <redacted> {escaped}"
+    );
+    assert_eq!(
+        SensorError::Misread(1, 2).to_string(),
+        "[S3] sensor 1 misread 2 (again: 1)"
+    );
+    assert_eq!(
+        SensorError::Buffer {
+            source: BufferError { len: 4, needed: 16 }
+        }
+        .to_string(),
+        "buffer of 4 bytes is too small, need 0x10"
+    );
+    assert_eq!(
+        SensorError::MaybeBuffer { source: None }.to_string(),
+        "[S4] no buffer"
+    );
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "alloc")]
+
+use core::error::Error as _;
+
+use easyerr::prelude::*;
+use easyerr::{Described, DescribedCtx};
+
+#[derive(Debug, Error)]
+#[error("not found")]
+struct NotFoundError;
+
+#[derive(Debug, Error)]
+enum AppError {
+    #[error(transparent)]
+    Config { source: Described<NotFoundError> },
+    #[error("failed to start")]
+    Start { source: Described<NotFoundError> },
+}
+
+fn load(name: &str) -> Result<(), Described<NotFoundError>> {
+    Err(NotFoundError).describe_with(|| format!("loading {name}"))
+}
+
+#[test]
+fn display() {
+    let err = Err::<(), _>(NotFoundError)
+        .describe("loading config")
+        .unwrap_err();
+    assert_eq!(err.to_string(), "loading config");
+    assert_eq!(format!("{err:#}"), "loading config: not found");
+    assert_eq!(err.message(), "loading config");
+    assert_eq!(err.source().unwrap().to_string(), "not found");
+}
+
+#[test]
+fn wrapped() {
+    let err = load("app.toml").context(AppCtx::Config).unwrap_err();
+    assert_eq!(format!("{err:#}"), "loading app.toml: not found");
+
+    let err = load("app.toml").context(AppCtx::Start).unwrap_err();
+    assert_eq!(
+        format!("{err:#}"),
+        "failed to start: loading app.toml: not found"
+    );
+}
+
+#[test]
+fn ctx() {
+    let err = Err::<(), _>(NotFoundError)
+        .context(DescribedCtx::new("loading data"))
+        .unwrap_err();
+    assert_eq!(format!("{err:#}"), "loading data: not found");
+}
@@ -0,0 +1,71 @@
+use easyerr::prelude::*;
+use easyerr::ExitCodeError;
+
+#[derive(Debug, Error)]
+#[error("failed to read input", exit_code = 74)]
+struct InputError {
+    source: std::io::Error,
+}
+
+#[derive(Debug, Error)]
+enum CliError {
+    #[error("invalid usage", exit_code = 64)]
+    Usage,
+    #[error("something went wrong")]
+    Other,
+    #[error(transparent)]
+    Input { source: InputError },
+    #[error(transparent)]
+    Format { source: std::fmt::Error },
+    #[error(transparent, fallback = "no input")]
+    Stdin { source: Option<InputError> },
+}
+
+#[derive(Debug, Error)]
+#[error("nothing to do")]
+struct IdleError;
+
+fn input() -> InputError {
+    InputError {
+        source: std::io::Error::other("oops"),
+    }
+}
+
+#[test]
+fn exit_code() {
+    assert_eq!(input().exit_code(), 74);
+    assert_eq!(CliError::Usage.exit_code(), 64);
+    assert_eq!(CliError::Other.exit_code(), 1);
+    assert_eq!(CliError::Input { source: input() }.exit_code(), 74);
+    assert_eq!(
+        CliError::Format {
+            source: std::fmt::Error
+        }
+        .exit_code(),
+        1
+    );
+    assert_eq!(
+        CliError::Stdin {
+            source: Some(input())
+        }
+        .exit_code(),
+        74
+    );
+    assert_eq!(CliError::Stdin { source: None }.exit_code(), 1);
+    assert_eq!(IdleError.exit_code(), 1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn termination() {
+    use easyerr::Exit;
+    use std::process::{ExitCode, Termination};
+
+    assert_eq!(Exit::<CliError>(Ok(())).report(), ExitCode::SUCCESS);
+    assert_eq!(Exit(Err(CliError::Usage)).report(), ExitCode::from(64));
+    assert_eq!(
+        Exit::from(Err::<(), _>(CliError::Input { source: input() })).report(),
+        ExitCode::from(74)
+    );
+    assert_eq!(Exit(Err(IdleError)).report(), ExitCode::from(1));
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "eyre")]
+
+use easyerr::prelude::*;
+
+#[derive(Debug, Error)]
+#[error("connection refused")]
+struct RefusedError;
+
+#[derive(Debug, Error)]
+#[error("failed to sync with {host}")]
+struct SyncError {
+    source: eyre::Report,
+    host: String,
+}
+
+fn connect() -> eyre::Result<()> {
+    Err(eyre::Report::new(RefusedError).wrap_err("connecting"))
+}
+
+#[test]
+fn eyre_source() {
+    let err = connect()
+        .context(SyncCtx {
+            host: "example.com".into(),
+        })
+        .unwrap_err();
+    assert_eq!(
+        format!("{err:#}"),
+        "failed to sync with example.com: connecting: connection refused"
+    );
+
+    let report = eyre::Report::new(err);
+    let chain = report.chain().map(|e| e.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        chain,
+        [
+            "failed to sync with example.com",
+            "connecting",
+            "connection refused"
+        ]
+    );
+    assert!(report.root_cause().is::<RefusedError>());
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "std")]
+
+use std::{error::Error as _, io, path::PathBuf};
+
+use easyerr::prelude::*;
+use easyerr::ErrorCode;
+
+#[derive(Debug, Error)]
+#[error(boxed, max_size = 8)]
+pub enum ParseErrorKind {
+    #[error(
+        "failed to read {path:?}",
+        code = "E_READ",
+        help = "check that the file exists"
+    )]
+    Read { source: io::Error, path: PathBuf },
+    #[error("unexpected {found:?} at line {line}", code = "E_SYNTAX")]
+    Syntax {
+        line: usize,
+        found: String,
+        expected: Vec<String>,
+    },
+}
+
+#[derive(Debug, Error)]
+#[error(boxed, into_io)]
+#[cfg_attr(feature = "serde", error(serialize))]
+#[cfg_attr(feature = "miette", error(diagnostic))]
+#[cfg_attr(feature = "defmt", error(defmt))]
+pub enum FetchErrorKind {
+    #[error("request timed out", io_kind = TimedOut)]
+    Timeout,
+    #[error("bad status {f0}")]
+    BadStatus(u16),
+}
+
+#[derive(Debug, Error)]
+#[error("failed to load plugin", max_size = 32)]
+struct PluginError {
+    source: ParseError,
+    name: &'static str,
+}
+
+fn read(path: &str) -> Result<String, ParseError> {
+    std::fs::read_to_string(path).context(ParseCtx::Read { path: path.into() })
+}
+
+#[test]
+fn size() {
+    assert_eq!(size_of::<ParseError>(), size_of::<usize>());
+    assert_eq!(size_of::<Result<(), ParseError>>(), size_of::<usize>());
+}
+
+#[test]
+fn context() {
+    let err = read("/does/not/exist").unwrap_err();
+    assert!(matches!(err.kind(), ParseErrorKind::Read { .. }));
+    assert!(err.is_read());
+    assert!(!err.is_syntax());
+    assert_eq!(err.code(), "E_READ");
+    assert!(format!("{err:#}").starts_with(
+        "failed to read \"/does/not/exist\"\nhelp: check that the file exists\ncaused by: "
+    ));
+    assert!(err.source().unwrap().is::<io::Error>());
+
+    let err = read("/does/not/exist")
+        .context(PluginCtx { name: "fmt" })
+        .unwrap_err();
+    assert!(format!("{err:#}").starts_with(
+        "failed to load plugin\ncaused by: failed to read \"/does/not/exist\"\n\
+         help: check that the file exists\ncaused by: "
+    ));
+}
+
+#[test]
+fn kind() {
+    let mut err = ParseError::from(ParseErrorKind::Syntax {
+        line: 3,
+        found: "}".into(),
+        expected: vec![";".into()],
+    });
+    if let ParseErrorKind::Syntax { line, .. } = err.kind_mut() {
+        *line += 1;
+    }
+
+    assert_eq!(err.to_string(), "unexpected \"}\" at line 4");
+    assert_eq!(format!("{err:?}"), format!("{:?}", err.kind()),);
+    assert!(matches!(
+        err.into_kind(),
+        ParseErrorKind::Syntax { line: 4, .. }
+    ));
+}
+
+#[test]
+fn forwarding() {
+    let err = FetchError::from(FetchErrorKind::Timeout);
+    assert!(err.is_timeout());
+    assert!(!err.is_bad_status());
+    assert_eq!(io::Error::from(err).kind(), io::ErrorKind::TimedOut);
+
+    #[cfg(feature = "serde")]
+    assert_eq!(
+        serde_json::to_value(FetchError::from(FetchErrorKind::BadStatus(503))).unwrap()["kind"],
+        "FetchErrorKind::BadStatus"
+    );
+
+    #[cfg(feature = "miette")]
+    assert!(miette::Diagnostic::help(&FetchError::from(FetchErrorKind::Timeout)).is_none());
+
+    #[cfg(feature = "defmt")]
+    {
+        fn assert_format<T: defmt::Format>() {}
+        assert_format::<FetchError>();
+    }
+}
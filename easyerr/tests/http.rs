@@ -0,0 +1,141 @@
+#![cfg(feature = "alloc")]
+
+use easyerr::prelude::*;
+use easyerr::{ExtensionValue, HttpError, ProblemDetails};
+
+#[derive(Debug)]
+struct Region;
+
+#[derive(Debug, Error)]
+#[error(
+    "user {id} not found",
+    status = 404,
+    type_uri = "https://example.com/problems/not-found",
+    title = "Not Found"
+)]
+struct UserNotFoundError {
+    id: u32,
+    #[error(redact)]
+    email: String,
+}
+
+#[derive(Debug, Error)]
+enum ApiError {
+    #[error("invalid page {f0}", status = 400)]
+    InvalidPage(u32),
+    #[error("database is down")]
+    Database,
+    #[error(transparent)]
+    User { source: UserNotFoundError },
+    #[error("rate limited in {region:?}", status = 429)]
+    RateLimited {
+        region: Region,
+        retry: bool,
+        load: f32,
+    },
+    #[error(transparent)]
+    Other {
+        source: Box<dyn core::error::Error + Send + Sync>,
+    },
+    #[error("visits by {visitor} are forbidden", status = 403)]
+    Forbidden { visitor: String },
+}
+
+fn not_found() -> UserNotFoundError {
+    UserNotFoundError {
+        id: 7,
+        email: "bob@example.com".into(),
+    }
+}
+
+#[test]
+fn status() {
+    assert_eq!(not_found().status(), 404);
+    assert_eq!(ApiError::InvalidPage(3).status(), 400);
+    assert_eq!(ApiError::Database.status(), 500);
+    assert_eq!(
+        ApiError::User {
+            source: not_found()
+        }
+        .status(),
+        404
+    );
+    assert_eq!(
+        ApiError::User {
+            source: not_found()
+        }
+        .title(),
+        Some("Not Found")
+    );
+
+    let other = ApiError::Other {
+        source: "connection reset".into(),
+    };
+    assert_eq!(other.status(), 500);
+    assert_eq!(other.title(), None);
+    assert_eq!(other.problem_details().extensions, vec![]);
+}
+
+#[test]
+fn problem_details() {
+    let details = ApiError::User {
+        source: not_found(),
+    }
+    .problem_details();
+    assert_eq!(
+        details,
+        ProblemDetails {
+            type_uri: "https://example.com/problems/not-found",
+            title: Some("Not Found"),
+            status: 404,
+            detail: "user 7 not found".into(),
+            extensions: vec![
+                ("id", ExtensionValue::U64(7)),
+                ("email", "<redacted>".into())
+            ],
+        }
+    );
+
+    let details = ApiError::InvalidPage(3).problem_details();
+    assert_eq!(details.type_uri, "about:blank");
+    assert_eq!(details.title, None);
+    assert_eq!(details.extensions, vec![("0", ExtensionValue::U64(3))]);
+
+    let details = ApiError::RateLimited {
+        region: Region,
+        retry: true,
+        load: 0.5,
+    }
+    .problem_details();
+    assert_eq!(
+        details.extensions,
+        vec![
+            ("region", "Region".into()),
+            ("retry", ExtensionValue::Bool(true)),
+            ("load", ExtensionValue::F64(0.5)),
+        ]
+    );
+
+    let details = ApiError::Forbidden {
+        visitor: "bob".into(),
+    }
+    .problem_details();
+    assert_eq!(details.extensions, vec![("visitor", "bob".into())]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize() {
+    let value = serde_json::to_value(not_found().problem_details()).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "type": "https://example.com/problems/not-found",
+            "title": "Not Found",
+            "status": 404,
+            "detail": "user 7 not found",
+            "id": 7,
+            "email": "<redacted>",
+        })
+    );
+}
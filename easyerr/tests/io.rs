@@ -0,0 +1,72 @@
+#![cfg(feature = "std")]
+
+use easyerr::prelude::*;
+use std::io;
+
+#[derive(Debug, Error)]
+#[error("missing file {f0}", io_kind = NotFound)]
+struct MissingFileError(String);
+
+#[derive(Debug, Error)]
+#[error(into_io)]
+enum ReaderError {
+    #[error("reader was closed", io_kind = BrokenPipe)]
+    Closed,
+    #[error("bad header")]
+    BadHeader,
+    #[error(transparent)]
+    Io { source: io::Error },
+    #[error(transparent)]
+    Missing { source: MissingFileError },
+    #[error(transparent)]
+    Format { source: std::fmt::Error },
+    #[error(transparent, fallback = "no reader")]
+    Optional { source: Option<io::Error> },
+}
+
+#[test]
+fn io_kind() {
+    let err = io::Error::from(MissingFileError("a.txt".into()));
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(err.to_string(), "missing file a.txt");
+    assert!(err.get_ref().unwrap().is::<MissingFileError>());
+
+    let err = io::Error::from(ReaderError::Closed);
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    assert!(err.into_inner().unwrap().is::<ReaderError>());
+
+    let err = io::Error::from(ReaderError::BadHeader);
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+}
+
+#[test]
+fn transparent() {
+    let err = io::Error::from(ReaderError::Io {
+        source: io::ErrorKind::UnexpectedEof.into(),
+    });
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert!(err.get_ref().is_none());
+
+    let err = io::Error::from(ReaderError::Missing {
+        source: MissingFileError("b.txt".into()),
+    });
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.get_ref().unwrap().is::<MissingFileError>());
+
+    let err = io::Error::from(ReaderError::Format {
+        source: std::fmt::Error,
+    });
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    assert!(err.get_ref().unwrap().is::<ReaderError>());
+
+    let err = io::Error::from(ReaderError::Optional {
+        source: Some(io::ErrorKind::UnexpectedEof.into()),
+    });
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert!(err.get_ref().is_none());
+
+    let err = io::Error::from(ReaderError::Optional { source: None });
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    assert_eq!(err.to_string(), "no reader");
+    assert!(err.get_ref().unwrap().is::<ReaderError>());
+}
@@ -0,0 +1,148 @@
+#![cfg(all(feature = "log", feature = "std"))]
+
+use easyerr::prelude::*;
+use easyerr::Level;
+use log::kv::{Key, Value, VisitSource};
+use std::sync::Mutex;
+
+#[derive(Debug, Error)]
+#[error("failed to fetch {url}", level = warn)]
+struct FetchError {
+    url: String,
+    attempts: u32,
+    #[error(redact)]
+    token: String,
+    headers: Vec<(String, String)>,
+    source: std::io::Error,
+}
+
+#[derive(Debug, Error)]
+enum JobError {
+    #[error("job {f0} was cancelled", level = info)]
+    Cancelled(u32),
+    #[error(transparent)]
+    Fetch { source: FetchError },
+    #[error("job is nested {level} levels deep", level = debug)]
+    Nested { level: u32 },
+}
+
+#[derive(Debug, PartialEq)]
+struct Entry {
+    level: log::Level,
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+struct Logger(Mutex<Vec<Entry>>);
+
+impl log::Log for Logger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        struct Fields(Vec<(String, String)>);
+
+        impl<'kvs> VisitSource<'kvs> for Fields {
+            fn visit_pair(
+                &mut self,
+                key: Key<'kvs>,
+                value: Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                self.0.push((key.to_string(), value.to_string()));
+                Ok(())
+            }
+        }
+
+        let mut fields = Fields(Vec::new());
+        record.key_values().visit(&mut fields).unwrap();
+        self.0.lock().unwrap().push(Entry {
+            level: record.level(),
+            message: record.args().to_string(),
+            fields: fields.0,
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Logger = Logger(Mutex::new(Vec::new()));
+
+fn fields(fields: &[(&str, &str)]) -> Vec<(String, String)> {
+    fields
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn log() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let fetch = || FetchError {
+        url: "https://example.com".into(),
+        attempts: 3,
+        token: "hunter2".into(),
+        headers: vec![("accept".into(), "*/*".into())],
+        source: std::io::Error::other("connection reset"),
+    };
+
+    let result: Result<(), _> = Err(fetch());
+    assert!(result.log_err().is_err());
+
+    let result: Result<(), _> = Err(JobError::Cancelled(7));
+    let _ = result.log_err();
+
+    let result: Result<(), _> = Err(JobError::Fetch { source: fetch() });
+    let _ = result.log_err();
+
+    let result: Result<(), _> = Err(JobError::Fetch { source: fetch() });
+    let _ = result.inspect_err_log(Level::Error);
+
+    let result: Result<(), _> = Err(JobError::Nested { level: 3 });
+    let _ = result.log_err();
+
+    let result: Result<(), std::io::Error> = Ok(());
+    let _ = result
+        .map_err(|e| JobError::Cancelled(e.kind() as u32))
+        .log_err();
+
+    let fetch_fields = fields(&[
+        ("url", "https://example.com"),
+        ("attempts", "3"),
+        ("token", "<redacted>"),
+        ("headers", r#"[("accept", "*/*")]"#),
+    ]);
+    let entries = std::mem::take(&mut *LOGGER.0.lock().unwrap());
+    assert_eq!(
+        entries,
+        vec![
+            Entry {
+                level: log::Level::Warn,
+                message: "failed to fetch https://example.com: connection reset".into(),
+                fields: fetch_fields.clone(),
+            },
+            Entry {
+                level: log::Level::Info,
+                message: "job 7 was cancelled".into(),
+                fields: fields(&[("0", "7")]),
+            },
+            Entry {
+                level: log::Level::Warn,
+                message: "failed to fetch https://example.com: connection reset".into(),
+                fields: fetch_fields.clone(),
+            },
+            Entry {
+                level: log::Level::Error,
+                message: "failed to fetch https://example.com: connection reset".into(),
+                fields: fetch_fields,
+            },
+            Entry {
+                level: log::Level::Debug,
+                message: "job is nested 3 levels deep".into(),
+                fields: fields(&[("level", "3")]),
+            },
+        ]
+    );
+}
@@ -0,0 +1,161 @@
+#![cfg(feature = "miette")]
+
+use easyerr::prelude::*;
+use miette::{Diagnostic, LabeledSpan, NamedSource, Severity, SourceSpan};
+
+#[derive(Debug, Error)]
+#[error(
+    "unknown key `{key}`",
+    code = "config::unknown_key",
+    help = "did you mean `{suggestion}`?",
+    url = "https://example.com/docs/config#{suggestion}",
+    severity = warning
+)]
+struct UnknownKeyError {
+    key: String,
+    suggestion: String,
+    #[error(source_code)]
+    src: NamedSource<String>,
+    #[label("this key")]
+    span: SourceSpan,
+    #[label]
+    section: SourceSpan,
+    #[error(label = "this value")]
+    value: SourceSpan,
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "invalid value {0}",
+    help = "replace {} with a {1}",
+    url = "https://example.com/docs/types#{1}"
+)]
+struct InvalidValueError(
+    #[error(redact)] String,
+    String,
+    #[label("expected a {1}")] SourceSpan,
+);
+
+#[derive(Debug, Error)]
+#[error(diagnostic)]
+enum ConfigError {
+    #[error("failed to read config")]
+    Read { source: std::io::Error },
+    #[error("failed to load config")]
+    Load { source: UnknownKeyError },
+    #[error(transparent)]
+    UnknownKey { source: UnknownKeyError },
+    #[error(transparent, fallback = "no config found")]
+    Missing { source: Option<UnknownKeyError> },
+    #[error(transparent)]
+    Io { source: std::io::Error },
+}
+
+fn unknown_key() -> UnknownKeyError {
+    UnknownKeyError {
+        key: "colour".into(),
+        suggestion: "color".into(),
+        src: NamedSource::new("config.toml", "[ui]\ncolour = true\n".into()),
+        span: (5, 6).into(),
+        section: (0, 4).into(),
+        value: (14, 4).into(),
+    }
+}
+
+#[test]
+fn diagnostic() {
+    let err = unknown_key();
+    assert_eq!(
+        Diagnostic::code(&err).unwrap().to_string(),
+        "config::unknown_key"
+    );
+    assert_eq!(err.help().unwrap().to_string(), "did you mean `color`?");
+    assert_eq!(
+        err.url().unwrap().to_string(),
+        "https://example.com/docs/config#color"
+    );
+    assert_eq!(err.severity(), Some(Severity::Warning));
+    assert!(err.source_code().is_some());
+    assert_eq!(
+        err.labels().unwrap().collect::<Vec<_>>(),
+        [
+            LabeledSpan::new_with_span(Some("this key".into()), (5, 6)),
+            LabeledSpan::new_with_span(None, (0, 4)),
+            LabeledSpan::new_with_span(Some("this value".into()), (14, 4)),
+        ]
+    );
+}
+
+#[test]
+fn positional() {
+    let err = InvalidValueError("yes".into(), "bool".into(), (7, 3).into());
+    assert_eq!(
+        err.help().unwrap().to_string(),
+        "replace <redacted> with a bool"
+    );
+    assert_eq!(
+        format!("{:+}", err.help().unwrap()),
+        "replace yes with a bool"
+    );
+    assert_eq!(
+        err.url().unwrap().to_string(),
+        "https://example.com/docs/types#bool"
+    );
+    assert_eq!(
+        err.labels().unwrap().collect::<Vec<_>>(),
+        [LabeledSpan::new_with_span(
+            Some("expected a bool".into()),
+            (7, 3)
+        )]
+    );
+}
+
+#[test]
+fn transparent() {
+    let err = ConfigError::UnknownKey {
+        source: unknown_key(),
+    };
+    assert_eq!(err.help().unwrap().to_string(), "did you mean `color`?");
+    assert_eq!(err.labels().unwrap().count(), 3);
+    assert!(err.source_code().is_some());
+
+    let err = ConfigError::Read {
+        source: std::io::Error::other("oops"),
+    };
+    assert!(err.help().is_none());
+    assert!(err.labels().is_none());
+    assert!(err.severity().is_none());
+    assert!(err.diagnostic_source().is_none());
+
+    let err = ConfigError::Missing {
+        source: Some(unknown_key()),
+    };
+    assert_eq!(err.help().unwrap().to_string(), "did you mean `color`?");
+    let err = ConfigError::Missing { source: None };
+    assert_eq!(err.to_string(), "no config found");
+    assert!(err.help().is_none());
+
+    let err = ConfigError::Io {
+        source: std::io::Error::other("oops"),
+    };
+    assert!(err.labels().is_none());
+}
+
+#[test]
+fn diagnostic_source() {
+    let err = ConfigError::Load {
+        source: unknown_key(),
+    };
+    let source = err.diagnostic_source().unwrap();
+    assert_eq!(source.to_string(), "unknown key `colour`");
+    assert_eq!(source.help().unwrap().to_string(), "did you mean `color`?");
+    assert!(err.help().is_none());
+}
+
+#[test]
+fn context() {
+    let result: Result<(), _> = Err(unknown_key());
+    let report = miette::Report::new(result.context(ConfigCtx::UnknownKey).unwrap_err());
+    assert_eq!(report.to_string(), "unknown key `colour`");
+    assert_eq!(report.help().unwrap().to_string(), "did you mean `color`?");
+}
@@ -0,0 +1,98 @@
+#![cfg(easyerr_minimal)]
+
+use easyerr::prelude::*;
+
+#[derive(Debug, Error)]
+#[error("failed to read {path}")]
+struct ReadError {
+    path: String,
+    source: std::io::Error,
+}
+
+#[derive(Debug, Error)]
+enum FlashError {
+    #[error("failed to load firmware")]
+    Load { source: ReadError },
+    #[error(transparent)]
+    Read { source: ReadError },
+    #[error(transparent, fallback = "no firmware")]
+    Missing { source: Option<ReadError> },
+}
+
+#[derive(Debug, Error)]
+#[error("sector {f0} is locked", errno = -13)]
+struct LockedError(u32);
+
+#[derive(Debug, Error)]
+enum BootError {
+    #[error("watchdog fired")]
+    Watchdog = 3,
+    #[error("brownout")]
+    Brownout,
+}
+
+fn read() -> Result<(), std::io::Error> {
+    Err(std::io::Error::other("oops"))
+}
+
+#[test]
+fn minimal() {
+    let err = read()
+        .context(ReadCtx {
+            path: "fw.bin".into(),
+        })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "ReadError");
+    assert_eq!(format!("{err:#}"), "ReadError: oops");
+
+    let err = Err::<(), _>(err).context(FlashCtx::Load).unwrap_err();
+    assert_eq!(err.to_string(), "FlashError::Load");
+    assert_eq!(format!("{err:#}"), "FlashError::Load: ReadError: oops");
+
+    assert_eq!(LockedError(3).to_string(), "-13");
+    assert_eq!(BootError::Watchdog.to_string(), "3");
+    assert_eq!(BootError::Brownout.to_string(), "4");
+    assert_eq!(
+        FlashError::Read {
+            source: ReadError {
+                path: "fw.bin".into(),
+                source: std::io::Error::other("oops"),
+            }
+        }
+        .to_string(),
+        "ReadError"
+    );
+    assert_eq!(
+        FlashError::Missing { source: None }.to_string(),
+        "FlashError::Missing"
+    );
+}
+
+#[cfg(feature = "catalog")]
+#[test]
+fn catalog() {
+    let entry = easyerr::catalog::find("ReadError").unwrap();
+    assert_eq!(entry.message, None);
+}
+
+#[cfg(feature = "miette")]
+#[test]
+fn diagnostic() {
+    use miette::{Diagnostic, SourceSpan};
+
+    #[derive(Debug, Error)]
+    #[error("unknown key {key}", help = "remove {key}", url = "https://example.com/{key}")]
+    struct UnknownKeyError {
+        key: String,
+        #[label("this key")]
+        span: SourceSpan,
+    }
+
+    let err = UnknownKeyError {
+        key: "colour".into(),
+        span: (0, 6).into(),
+    };
+    assert!(err.help().is_none());
+    assert!(err.url().is_none());
+    assert_eq!(err.labels().unwrap().next().unwrap().label(), None);
+}
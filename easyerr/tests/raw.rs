@@ -0,0 +1,98 @@
+use easyerr::prelude::*;
+use easyerr::RawError;
+
+#[derive(Debug, Error)]
+#[error("timed out", errno = -110)]
+struct TimeoutError;
+
+#[derive(Debug, Error)]
+enum DbError {
+    #[error("database is locked", errno = -1)]
+    Locked,
+    #[error("no such table {table}", errno = -2)]
+    NoSuchTable { table: String },
+    #[error("failed to open database", errno = -5)]
+    Open { source: std::io::Error },
+    #[error(transparent)]
+    Timeout { source: TimeoutError },
+}
+
+#[derive(Debug, Error)]
+enum Status {
+    #[error("busy")]
+    Busy = 16,
+    #[error("again")]
+    Again = 11,
+    #[error("no memory")]
+    NoMemory,
+    #[error("access denied")]
+    Access,
+}
+
+#[test]
+fn as_raw() {
+    assert_eq!(TimeoutError.as_raw(), -110);
+    assert_eq!(DbError::Locked.as_raw(), -1);
+    assert_eq!(
+        DbError::NoSuchTable {
+            table: "users".into()
+        }
+        .as_raw(),
+        -2
+    );
+    assert_eq!(
+        DbError::Timeout {
+            source: TimeoutError
+        }
+        .as_raw(),
+        -110
+    );
+    assert_eq!(Status::Busy.as_raw(), 16);
+    assert_eq!(Status::Again.as_raw(), 11);
+    assert_eq!(Status::NoMemory.as_raw(), 12);
+    assert_eq!(Status::Access.as_raw(), 13);
+}
+
+#[test]
+fn from_raw() {
+    assert!(matches!(TimeoutError::from_raw(-110), Some(TimeoutError)));
+    assert!(matches!(DbError::from_raw(-1), Some(DbError::Locked)));
+    assert!(DbError::from_raw(-2).is_none());
+    assert!(DbError::from_raw(0).is_none());
+    assert!(matches!(Status::from_raw(11), Some(Status::Again)));
+    assert!(matches!(Status::from_raw(13), Some(Status::Access)));
+}
+
+#[test]
+fn raw_os_error() {
+    let err = DbError::Open {
+        source: std::io::Error::from_raw_os_error(13),
+    };
+
+    let expected = cfg!(feature = "std").then_some(13);
+    assert_eq!(err.raw_os_error(), expected);
+    assert_eq!(DbError::Locked.raw_os_error(), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn last_error() {
+    use easyerr::ffi;
+
+    ffi::clear_last_error();
+    assert_eq!(ffi::last_error_message(), None);
+    assert_eq!(ffi::last_error_length(), 0);
+
+    assert_eq!(ffi::report(&DbError::Locked), -1);
+    assert_eq!(
+        ffi::last_error_message().as_deref(),
+        Some("database is locked")
+    );
+    assert_eq!(ffi::last_error_length(), 19);
+
+    let mut buf = [0; 32];
+    let written = unsafe { ffi::copy_last_error(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(written, 19);
+    assert_eq!(&buf[..19], b"database is locked\0".map(|b| b as _));
+    assert_eq!(unsafe { ffi::copy_last_error(buf.as_mut_ptr(), 4) }, -1);
+}
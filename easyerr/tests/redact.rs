@@ -0,0 +1,23 @@
+#![cfg(feature = "std")]
+
+use easyerr::{unredacted, Redacted, Redaction};
+
+#[test]
+fn unredacted_is_per_thread() {
+    let secret = "hunter2";
+    let revealed = unredacted(|| {
+        let other =
+            std::thread::spawn(move || Redacted::scoped(&secret, Redaction::Hide).to_string())
+                .join()
+                .unwrap();
+        assert_eq!(other, "<redacted>");
+
+        Redacted::scoped(&secret, Redaction::Hide).to_string()
+    });
+
+    assert_eq!(revealed, "hunter2");
+    assert_eq!(
+        Redacted::scoped(&secret, Redaction::Hide).to_string(),
+        "<redacted>"
+    );
+}
@@ -0,0 +1,157 @@
+#![cfg(feature = "serde")]
+
+use easyerr::prelude::*;
+use easyerr::SerializableChain;
+use serde_json::json;
+
+#[derive(Debug, Error)]
+#[error("invalid key {key}", serialize)]
+struct InvalidKeyError {
+    key: String,
+    #[error(redact)]
+    value: String,
+    #[error(serialize = false)]
+    line: Vec<u8>,
+}
+
+#[derive(Debug, Error)]
+#[error("unknown user {email}", serialize)]
+struct UnknownUserError {
+    #[error(redact = "hash")]
+    email: String,
+    #[error(redact = "hash")]
+    roles: Vec<String>,
+}
+
+#[derive(Debug, Error)]
+#[error(serialize)]
+enum ConfigError {
+    #[error("failed to read {path}")]
+    Read {
+        source: std::io::Error,
+        path: String,
+    },
+    #[error("failed to parse config")]
+    Parse { source: InvalidKeyError },
+    #[error("unexpected byte {f0:#x}")]
+    Byte(u8),
+    #[error("unknown map {map}")]
+    UnknownMap { map: String },
+    #[error(transparent)]
+    Other { source: InvalidKeyError },
+}
+
+fn invalid_key() -> InvalidKeyError {
+    InvalidKeyError {
+        key: "port".into(),
+        value: "hunter2".into(),
+        line: Vec::new(),
+    }
+}
+
+#[test]
+fn serializes_error() {
+    let e = ConfigError::Byte(0xff);
+    assert_eq!(
+        serde_json::to_value(&e).unwrap(),
+        json!({
+            "kind": "ConfigError::Byte",
+            "message": "unexpected byte 0xff",
+            "fields": { "0": 255 },
+            "source": null,
+        })
+    );
+
+    assert_eq!(
+        serde_json::to_value(invalid_key()).unwrap(),
+        json!({
+            "kind": "InvalidKeyError",
+            "message": "invalid key port",
+            "fields": { "key": "port", "value": "<redacted>" },
+            "source": null,
+        })
+    );
+
+    let value = easyerr::unredacted(|| serde_json::to_value(invalid_key()).unwrap());
+    assert_eq!(value["fields"]["value"], "hunter2");
+
+    let e = ConfigError::UnknownMap {
+        map: "dvorak".into(),
+    };
+    let value = serde_json::to_value(&e).unwrap();
+    assert_eq!(value["fields"], json!({ "map": "dvorak" }));
+}
+
+#[test]
+fn hashes_match_display() {
+    let e = UnknownUserError {
+        email: "bob@example.com".into(),
+        roles: vec!["admin".into()],
+    };
+    let message = e.to_string();
+    let hash = message.strip_prefix("unknown user ").unwrap();
+    assert!(hash.starts_with("<redacted:"));
+
+    let value = serde_json::to_value(&e).unwrap();
+    assert_eq!(value["message"], message);
+    assert_eq!(value["fields"]["email"], hash);
+
+    // fields without a `Display` impl are hashed through their `Debug` impl
+    let roles = value["fields"]["roles"].as_str().unwrap();
+    assert!(roles.starts_with("<redacted:"));
+    assert_ne!(roles, hash);
+}
+
+#[test]
+fn serializes_chain() {
+    let e = Err::<(), _>(invalid_key())
+        .context(ConfigCtx::Parse)
+        .unwrap_err();
+    assert_eq!(
+        serde_json::to_value(&e).unwrap(),
+        json!({
+            "kind": "ConfigError::Parse",
+            "message": "failed to parse config",
+            "fields": {},
+            "source": { "message": "invalid key port", "source": null },
+        })
+    );
+
+    let e = Err::<(), _>(std::io::Error::other("disk on fire"))
+        .context(ConfigCtx::Read {
+            path: "a.toml".into(),
+        })
+        .unwrap_err();
+    assert_eq!(
+        serde_json::to_value(&e).unwrap()["source"],
+        json!({ "message": "disk on fire", "source": null })
+    );
+
+    let e = ConfigError::Other {
+        source: invalid_key(),
+    };
+    assert_eq!(
+        serde_json::to_value(&e).unwrap(),
+        json!({
+            "kind": "ConfigError::Other",
+            "message": "invalid key port",
+            "fields": {},
+            "source": null,
+        })
+    );
+}
+
+#[test]
+fn serializes_any_error() {
+    let e = Err::<(), _>(invalid_key())
+        .context(ConfigCtx::Parse)
+        .unwrap_err();
+    let e: &dyn std::error::Error = &e;
+    assert_eq!(
+        serde_json::to_value(SerializableChain(e)).unwrap(),
+        json!({
+            "message": "failed to parse config",
+            "source": { "message": "invalid key port", "source": null },
+        })
+    );
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "alloc")]
+
+use easyerr::prelude::*;
+use easyerr::ErrorSnapshot;
+use std::error::Error as _;
+
+#[derive(Debug, Error)]
+#[error("failed to read {path}")]
+struct ReadError {
+    source: std::io::Error,
+    path: String,
+}
+
+fn read_error() -> ReadError {
+    Err::<(), _>(std::io::Error::other("disk on fire"))
+        .context(ReadCtx {
+            path: "a.txt".into(),
+        })
+        .unwrap_err()
+}
+
+#[test]
+fn captures_chain() {
+    let snapshot = ErrorSnapshot::new(&read_error());
+    assert_eq!(snapshot.type_name(), Some("snapshot::ReadError"));
+    assert_eq!(snapshot.message(), "failed to read a.txt");
+    assert_eq!(snapshot.to_string(), "failed to read a.txt");
+    assert_eq!(
+        format!("{snapshot:#}"),
+        "failed to read a.txt: disk on fire"
+    );
+
+    let source = snapshot.snapshot_source().unwrap();
+    assert_eq!(source.type_name(), None);
+    assert_eq!(source.message(), "disk on fire");
+    assert!(source.source().is_none());
+
+    let cloned = snapshot.clone();
+    assert_eq!(cloned, snapshot);
+    assert_eq!(ErrorSnapshot::new(&snapshot), snapshot);
+}
+
+#[test]
+fn is_send_sync() {
+    fn assert_send_sync<T: Send + Sync + Clone + std::error::Error>() {}
+    assert_send_sync::<ErrorSnapshot>();
+
+    let snapshot = ErrorSnapshot::new(&read_error());
+    let handle = std::thread::spawn(move || snapshot.to_string());
+    assert_eq!(handle.join().unwrap(), "failed to read a.txt");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn round_trips_through_serde() {
+    let snapshot = ErrorSnapshot::new(&read_error());
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let rebuilt: ErrorSnapshot = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(rebuilt, snapshot);
+    assert_eq!(format!("{rebuilt:#}"), "failed to read a.txt: disk on fire");
+}
@@ -0,0 +1,134 @@
+#![cfg(all(feature = "tracing", feature = "std"))]
+
+use easyerr::prelude::*;
+use easyerr::Level;
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+#[derive(Debug, Error)]
+#[error("failed to fetch {url}", level = warn)]
+struct FetchError {
+    url: String,
+    #[error(redact)]
+    token: String,
+}
+
+#[derive(Debug, Error)]
+enum JobError {
+    #[error(transparent)]
+    Fetch { source: FetchError },
+    #[error("job is nested {level} levels deep", level = info)]
+    Nested { level: u32 },
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Entry {
+    level: String,
+    fields: Vec<(String, String)>,
+}
+
+#[derive(Clone, Default)]
+struct Collector(Arc<Mutex<Vec<Entry>>>);
+
+impl Visit for Entry {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields
+            .push((field.name().to_string(), format!("{value:?}")));
+    }
+}
+
+impl Subscriber for Collector {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut entry = Entry {
+            level: event.metadata().level().to_string(),
+            fields: Vec::new(),
+        };
+
+        event.record(&mut entry);
+        self.0.lock().unwrap().push(entry);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[test]
+fn tracing() {
+    let collector = Collector::default();
+    tracing::subscriber::with_default(collector.clone(), || {
+        let result: Result<(), _> = Err(FetchError {
+            url: "https://example.com".into(),
+            token: "hunter2".into(),
+        });
+
+        let _ = result.log_err();
+        let _ = Err::<(), _>(std::io::Error::other("oops"))
+            .map_err(|source| FetchError {
+                url: source.to_string(),
+                token: String::new(),
+            })
+            .inspect_err_log(Level::Debug);
+
+        let _ = Err::<(), _>(JobError::Fetch {
+            source: FetchError {
+                url: "https://example.org".into(),
+                token: "hunter2".into(),
+            },
+        })
+        .log_err();
+        let _ = Err::<(), _>(JobError::Nested { level: 2 }).log_err();
+    });
+
+    let entries = std::mem::take(&mut *collector.0.lock().unwrap());
+    let fields = |url: &str| {
+        vec![
+            ("message".to_string(), format!("failed to fetch {url}")),
+            ("url".to_string(), url.to_string()),
+            ("token".to_string(), "<redacted>".to_string()),
+        ]
+    };
+
+    assert_eq!(
+        entries,
+        vec![
+            Entry {
+                level: "WARN".into(),
+                fields: fields("https://example.com"),
+            },
+            Entry {
+                level: "DEBUG".into(),
+                fields: fields("oops"),
+            },
+            Entry {
+                level: "WARN".into(),
+                fields: fields("https://example.org"),
+            },
+            Entry {
+                level: "INFO".into(),
+                fields: vec![
+                    ("message".into(), "job is nested 2 levels deep".into()),
+                    ("level".into(), "2".into()),
+                ],
+            },
+        ]
+    );
+}
@@ -0,0 +1,485 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Error, Expr, Ident, LitBool, LitInt, LitStr, Meta, Token, Type,
+};
+
+/// Where an `#[error(...)]` attribute was found.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Struct,
+    Enum,
+    Variant,
+    Field,
+}
+
+/// The message of a struct or variant.
+#[derive(Clone)]
+pub enum Message {
+    Format(LitStr),
+    Transparent(Span),
+}
+
+/// How a `#[error(redact)]` field is rendered while redacted.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RedactMode {
+    Hide,
+    Hash,
+}
+
+impl ToTokens for RedactMode {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            Self::Hide => quote! { ::easyerr::Redaction::Hide },
+            Self::Hash => quote! { ::easyerr::Redaction::Hash },
+        });
+    }
+}
+
+/// How the source field of a struct or variant is stored.
+#[derive(Clone)]
+pub enum SourceMode {
+    /// Boxed as a `dyn Error`. Contexts accept any error which can be boxed.
+    Boxed,
+    /// An error report type which derefs to `dyn Error`, like `anyhow::Error`.
+    Report,
+    /// Converted from another type with a function. Contexts accept the other type.
+    From(Box<Type>, Box<Expr>),
+}
+
+/// The text of a `#[label]` (or `#[error(label)]`) field.
+#[derive(Clone)]
+pub enum LabelText {
+    None,
+    Text(LitStr),
+}
+
+/// Which `Debug` impl the derive should generate, if any.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DebugMode {
+    Fields,
+    Report,
+}
+
+enum ErrorAttrArg {
+    Message(Message),
+    Fallback(LitStr),
+    Debug(DebugMode),
+    Redact(RedactMode),
+    Code(LitStr),
+    ShowCode,
+    Serialize(bool),
+    Skip,
+    Status(LitInt),
+    TypeUri(LitStr),
+    Title(LitStr),
+    ExitCode(LitInt),
+    IoKind(Ident),
+    IntoIo,
+    Errno(LitInt),
+    Level(Ident),
+    Defmt,
+    Diagnostic,
+    Help(LitStr),
+    Url(LitStr),
+    Severity(Ident),
+    SourceCode,
+    Label(LabelText),
+    Note(LitStr),
+    Source(SourceMode),
+    Boxed,
+    MaxSize(LitInt),
+}
+
+impl ErrorAttrArg {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Message(_) => "message",
+            Self::Fallback(_) => "fallback",
+            Self::Debug(_) => "debug",
+            Self::Redact(_) => "redact",
+            Self::Code(_) => "code",
+            Self::ShowCode => "show_code",
+            Self::Serialize(_) => "serialize",
+            Self::Skip => "skip",
+            Self::Status(_) => "status",
+            Self::TypeUri(_) => "type_uri",
+            Self::Title(_) => "title",
+            Self::ExitCode(_) => "exit_code",
+            Self::IoKind(_) => "io_kind",
+            Self::IntoIo => "into_io",
+            Self::Errno(_) => "errno",
+            Self::Level(_) => "level",
+            Self::Defmt => "defmt",
+            Self::Diagnostic => "diagnostic",
+            Self::Help(_) => "help",
+            Self::Url(_) => "url",
+            Self::Severity(_) => "severity",
+            Self::SourceCode => "source_code",
+            Self::Label(_) => "label",
+            Self::Note(_) => "note",
+            Self::Source(_) => "source",
+            Self::Boxed => "boxed",
+            Self::MaxSize(_) => "max_size",
+        }
+    }
+
+    fn allowed_in(&self, location: Location) -> bool {
+        use Location as L;
+
+        match self {
+            Self::Message(_)
+            | Self::Fallback(_)
+            | Self::Code(_)
+            | Self::ExitCode(_)
+            | Self::IoKind(_)
+            | Self::Errno(_)
+            | Self::Level(_)
+            | Self::Help(_)
+            | Self::Note(_)
+            | Self::Url(_)
+            | Self::Severity(_) => matches!(location, L::Struct | L::Variant),
+            Self::Status(_) | Self::TypeUri(_) | Self::Title(_) => {
+                matches!(location, L::Struct | L::Variant)
+            }
+            Self::Debug(_) | Self::ShowCode | Self::IntoIo | Self::Defmt | Self::Diagnostic => {
+                matches!(location, L::Struct | L::Enum)
+            }
+            Self::Redact(_) | Self::SourceCode | Self::Label(_) | Self::Source(_) | Self::Skip => {
+                location == L::Field
+            }
+            Self::Serialize(_) => matches!(location, L::Struct | L::Enum | L::Field),
+            Self::Boxed => location == L::Enum,
+            Self::MaxSize(_) => matches!(location, L::Struct | L::Enum),
+        }
+    }
+}
+
+/// Parses the `= value` part of a `name = value` argument.
+fn parse_value<T: Parse>(input: ParseStream) -> syn::Result<T> {
+    input.parse::<Token![=]>()?;
+    input.parse()
+}
+
+struct SpannedArg {
+    span: Span,
+    arg: ErrorAttrArg,
+}
+
+impl Parse for SpannedArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(LitStr) {
+            let format_str: LitStr = input.parse()?;
+            return Ok(Self {
+                span: format_str.span(),
+                arg: ErrorAttrArg::Message(Message::Format(format_str)),
+            });
+        }
+
+        let ident: Ident = input.parse().map_err(|e| {
+            Error::new(
+                e.span(),
+                "unknown error argument. expected a format string or an argument name.",
+            )
+        })?;
+        let span = ident.span();
+
+        let arg = if ident == "transparent" {
+            ErrorAttrArg::Message(Message::Transparent(span))
+        } else if ident == "debug" {
+            if input.peek(Token![=]) {
+                let mode: LitStr = parse_value(input)?;
+                match mode.value().as_str() {
+                    "fields" => ErrorAttrArg::Debug(DebugMode::Fields),
+                    "report" => ErrorAttrArg::Debug(DebugMode::Report),
+                    _ => {
+                        return Err(Error::new(
+                            mode.span(),
+                            "unknown debug mode. valid modes are \"fields\" and \"report\".",
+                        ))
+                    }
+                }
+            } else {
+                ErrorAttrArg::Debug(DebugMode::Fields)
+            }
+        } else if ident == "redact" {
+            if input.peek(Token![=]) {
+                let mode: LitStr = parse_value(input)?;
+                match mode.value().as_str() {
+                    "hide" => ErrorAttrArg::Redact(RedactMode::Hide),
+                    "hash" => ErrorAttrArg::Redact(RedactMode::Hash),
+                    _ => {
+                        return Err(Error::new(
+                            mode.span(),
+                            "unknown redaction mode. valid modes are \"hide\" and \"hash\".",
+                        ))
+                    }
+                }
+            } else {
+                ErrorAttrArg::Redact(RedactMode::Hide)
+            }
+        } else if ident == "fallback" {
+            ErrorAttrArg::Fallback(parse_value(input)?)
+        } else if ident == "code" {
+            ErrorAttrArg::Code(parse_value(input)?)
+        } else if ident == "show_code" {
+            ErrorAttrArg::ShowCode
+        } else if ident == "serialize" {
+            if input.peek(Token![=]) {
+                ErrorAttrArg::Serialize(parse_value::<LitBool>(input)?.value)
+            } else {
+                ErrorAttrArg::Serialize(true)
+            }
+        } else if ident == "skip" {
+            ErrorAttrArg::Skip
+        } else if ident == "status" {
+            let status: LitInt = parse_value(input)?;
+            if !(100..=599).contains(&status.base10_parse::<u16>()?) {
+                return Err(Error::new(
+                    status.span(),
+                    "invalid HTTP status code. it must be between 100 and 599.",
+                ));
+            }
+
+            ErrorAttrArg::Status(status)
+        } else if ident == "type_uri" {
+            ErrorAttrArg::TypeUri(parse_value(input)?)
+        } else if ident == "title" {
+            ErrorAttrArg::Title(parse_value(input)?)
+        } else if ident == "exit_code" {
+            let code: LitInt = parse_value(input)?;
+            if !(1..=255).contains(&code.base10_parse::<u16>()?) {
+                return Err(Error::new(
+                    code.span(),
+                    "invalid exit code. it must be between 1 and 255.",
+                ));
+            }
+
+            ErrorAttrArg::ExitCode(code)
+        } else if ident == "io_kind" {
+            ErrorAttrArg::IoKind(parse_value(input)?)
+        } else if ident == "into_io" {
+            ErrorAttrArg::IntoIo
+        } else if ident == "errno" {
+            let errno: LitInt = parse_value(input)?;
+            errno.base10_parse::<i32>()?;
+
+            ErrorAttrArg::Errno(errno)
+        } else if ident == "level" {
+            let level: Ident = parse_value(input)?;
+            let variant = match level.to_string().as_str() {
+                "error" => "Error",
+                "warn" => "Warn",
+                "info" => "Info",
+                "debug" => "Debug",
+                "trace" => "Trace",
+                _ => {
+                    return Err(Error::new(
+                        level.span(),
+                        "unknown level. valid levels are error, warn, info, debug and trace.",
+                    ))
+                }
+            };
+
+            ErrorAttrArg::Level(Ident::new(variant, level.span()))
+        } else if ident == "defmt" {
+            ErrorAttrArg::Defmt
+        } else if ident == "diagnostic" {
+            ErrorAttrArg::Diagnostic
+        } else if ident == "help" {
+            ErrorAttrArg::Help(parse_value(input)?)
+        } else if ident == "note" {
+            ErrorAttrArg::Note(parse_value(input)?)
+        } else if ident == "url" {
+            ErrorAttrArg::Url(parse_value(input)?)
+        } else if ident == "severity" {
+            let severity: Ident = parse_value(input)?;
+            let variant = match severity.to_string().as_str() {
+                "error" => "Error",
+                "warning" => "Warning",
+                "advice" => "Advice",
+                _ => {
+                    return Err(Error::new(
+                        severity.span(),
+                        "unknown severity. valid severities are error, warning and advice.",
+                    ))
+                }
+            };
+
+            ErrorAttrArg::Severity(Ident::new(variant, severity.span()))
+        } else if ident == "source_code" {
+            ErrorAttrArg::SourceCode
+        } else if ident == "label" {
+            if input.peek(Token![=]) {
+                ErrorAttrArg::Label(LabelText::Text(parse_value(input)?))
+            } else {
+                ErrorAttrArg::Label(LabelText::None)
+            }
+        } else if ident == "boxed" {
+            ErrorAttrArg::Boxed
+        } else if ident == "max_size" {
+            let size: LitInt = parse_value(input)?;
+            size.base10_parse::<usize>()?;
+
+            ErrorAttrArg::MaxSize(size)
+        } else if ident == "source" {
+            let content;
+            parenthesized!(content in input);
+            let mode: Ident = content.parse()?;
+            if mode == "boxed" {
+                ErrorAttrArg::Source(SourceMode::Boxed)
+            } else if mode == "report" {
+                ErrorAttrArg::Source(SourceMode::Report)
+            } else if mode == "from" {
+                let args;
+                parenthesized!(args in content);
+                let ty: Type = args.parse()?;
+                args.parse::<Token![,]>()?;
+                let convert: Expr = args.parse()?;
+                args.parse::<Option<Token![,]>>()?;
+
+                ErrorAttrArg::Source(SourceMode::From(Box::new(ty), Box::new(convert)))
+            } else {
+                return Err(Error::new(
+                    mode.span(),
+                    "unknown source mode. valid modes are `boxed`, `report` and `from(Type, convert)`.",
+                ));
+            }
+        } else {
+            return Err(Error::new(
+                span,
+                format!("unknown error argument `{ident}`"),
+            ));
+        };
+
+        Ok(Self { span, arg })
+    }
+}
+
+/// All the arguments of the `#[error(...)]` attributes of an item.
+#[derive(Clone, Default)]
+pub struct ErrorAttrs {
+    pub message: Option<Message>,
+    pub fallback: Option<LitStr>,
+    pub debug: Option<DebugMode>,
+    pub redact: Option<RedactMode>,
+    pub code: Option<LitStr>,
+    pub show_code: bool,
+    pub serialize: Option<bool>,
+    pub skip: bool,
+    pub status: Option<LitInt>,
+    pub type_uri: Option<LitStr>,
+    pub title: Option<LitStr>,
+    pub exit_code: Option<LitInt>,
+    pub io_kind: Option<Ident>,
+    pub into_io: bool,
+    pub errno: Option<LitInt>,
+    pub level: Option<Ident>,
+    pub defmt: bool,
+    pub diagnostic: bool,
+    pub help: Option<LitStr>,
+    pub url: Option<LitStr>,
+    pub severity: Option<Ident>,
+    pub source_code: bool,
+    pub label: Option<LabelText>,
+    pub notes: Vec<LitStr>,
+    pub source: Option<SourceMode>,
+    pub boxed: bool,
+    pub max_size: Option<LitInt>,
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, span: Span, name: &str) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(Error::new(span, format!("duplicate `{name}` argument")));
+    }
+
+    *slot = Some(value);
+    Ok(())
+}
+
+fn set_flag(flag: &mut bool, span: Span, name: &str) -> syn::Result<()> {
+    if *flag {
+        return Err(Error::new(span, format!("duplicate `{name}` argument")));
+    }
+
+    *flag = true;
+    Ok(())
+}
+
+impl ErrorAttrs {
+    pub fn parse(attrs: &[Attribute], location: Location) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("error")) {
+            let args =
+                attr.parse_args_with(Punctuated::<SpannedArg, Token![,]>::parse_terminated)?;
+
+            for SpannedArg { span, arg } in args {
+                if !arg.allowed_in(location) {
+                    let message = match arg {
+                        ErrorAttrArg::Boxed => {
+                            "`boxed` is only allowed on enums, which must be named `...Kind`"
+                                .to_owned()
+                        }
+                        _ => format!("`{}` is not allowed here", arg.name()),
+                    };
+
+                    return Err(Error::new(span, message));
+                }
+
+                let name = arg.name();
+                match arg {
+                    ErrorAttrArg::Message(m) => set_once(&mut result.message, m, span, name)?,
+                    ErrorAttrArg::Fallback(m) => set_once(&mut result.fallback, m, span, name)?,
+                    ErrorAttrArg::Debug(d) => set_once(&mut result.debug, d, span, name)?,
+                    ErrorAttrArg::Redact(r) => set_once(&mut result.redact, r, span, name)?,
+                    ErrorAttrArg::Code(c) => set_once(&mut result.code, c, span, name)?,
+                    ErrorAttrArg::ShowCode => set_flag(&mut result.show_code, span, name)?,
+                    ErrorAttrArg::Serialize(b) => set_once(&mut result.serialize, b, span, name)?,
+                    ErrorAttrArg::Skip => set_flag(&mut result.skip, span, name)?,
+                    ErrorAttrArg::Status(s) => set_once(&mut result.status, s, span, name)?,
+                    ErrorAttrArg::TypeUri(t) => set_once(&mut result.type_uri, t, span, name)?,
+                    ErrorAttrArg::Title(t) => set_once(&mut result.title, t, span, name)?,
+                    ErrorAttrArg::ExitCode(c) => set_once(&mut result.exit_code, c, span, name)?,
+                    ErrorAttrArg::IoKind(k) => set_once(&mut result.io_kind, k, span, name)?,
+                    ErrorAttrArg::IntoIo => set_flag(&mut result.into_io, span, name)?,
+                    ErrorAttrArg::Errno(e) => set_once(&mut result.errno, e, span, name)?,
+                    ErrorAttrArg::Level(l) => set_once(&mut result.level, l, span, name)?,
+                    ErrorAttrArg::Defmt => set_flag(&mut result.defmt, span, name)?,
+                    ErrorAttrArg::Diagnostic => set_flag(&mut result.diagnostic, span, name)?,
+                    ErrorAttrArg::Help(h) => set_once(&mut result.help, h, span, name)?,
+                    ErrorAttrArg::Url(u) => set_once(&mut result.url, u, span, name)?,
+                    ErrorAttrArg::Severity(s) => set_once(&mut result.severity, s, span, name)?,
+                    ErrorAttrArg::SourceCode => set_flag(&mut result.source_code, span, name)?,
+                    ErrorAttrArg::Label(l) => set_once(&mut result.label, l, span, name)?,
+                    ErrorAttrArg::Note(n) => result.notes.push(n),
+                    ErrorAttrArg::Source(m) => set_once(&mut result.source, m, span, name)?,
+                    ErrorAttrArg::Boxed => set_flag(&mut result.boxed, span, name)?,
+                    ErrorAttrArg::MaxSize(s) => set_once(&mut result.max_size, s, span, name)?,
+                }
+            }
+        }
+
+        for attr in attrs.iter().filter(|a| a.path().is_ident("label")) {
+            let span = attr.path().span();
+            if location != Location::Field {
+                return Err(Error::new(span, "`label` is not allowed here"));
+            }
+
+            let label = match &attr.meta {
+                Meta::Path(_) => LabelText::None,
+                _ => LabelText::Text(attr.parse_args()?),
+            };
+            set_once(&mut result.label, label, span, "label")?;
+        }
+
+        Ok(result)
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        matches!(self.message, Some(Message::Transparent(_)))
+    }
+}
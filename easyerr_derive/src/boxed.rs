@@ -0,0 +1,345 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Error, Generics, Ident, ItemEnum, LitInt};
+
+/// Optional traits implemented by a boxed enum, which its handle forwards as well. The traits
+/// which are always implemented (`LogError`, `ErrorFields`, `Hints` and `ExitCodeError`) are always
+/// forwarded.
+pub struct Forwarded {
+    pub code: bool,
+    pub http: bool,
+    pub raw: bool,
+    pub io: bool,
+    pub defmt: bool,
+    pub diagnostic: bool,
+    pub serialize: bool,
+}
+
+/// Name of the handle of the boxed enum `kind`: its name without the `Kind` suffix.
+pub fn handle_ident(kind: &Ident) -> syn::Result<Ident> {
+    let kind_str = kind.to_string();
+    match kind_str.strip_suffix("Kind") {
+        Some(handle) if !handle.is_empty() => Ok(format_ident!("{}", handle, span = kind.span())),
+        _ => Err(Error::new(
+            kind.span(),
+            "`boxed` enums must be named `...Kind`, so that their handle can take the name \
+             without the suffix",
+        )),
+    }
+}
+
+/// `ident` in snake case, e.g. `is_not_found` for `NotFound` with the `is_` prefix.
+fn snake_case(prefix: &str, ident: &Ident) -> Ident {
+    let ident = ident.to_string();
+    let chars: Vec<char> = ident.chars().collect();
+    let mut snake = String::from(prefix);
+    for (i, &c) in chars.iter().enumerate() {
+        let word_start = i > 0
+            && c.is_uppercase()
+            && (!chars[i - 1].is_uppercase() || chars.get(i + 1).is_some_and(|c| c.is_lowercase()));
+        if word_start {
+            snake.push('_');
+        }
+
+        snake.extend(c.to_lowercase());
+    }
+
+    format_ident!("{}", snake)
+}
+
+/// Generates the handle of the boxed enum `enum_`: a struct holding a `Box` of the enum, which
+/// implements the same traits by forwarding them to it, and has an `is_variant` method for each of
+/// its variants.
+pub fn generate_handle(
+    enum_: &ItemEnum,
+    handle: &Ident,
+    forwarded: &Forwarded,
+) -> syn::Result<TokenStream> {
+    if !cfg!(feature = "alloc") {
+        return Err(Error::new(
+            enum_.ident.span(),
+            "`boxed` requires the `alloc` feature of easyerr",
+        ));
+    }
+
+    let vis = &enum_.vis;
+    let kind = &enum_.ident;
+    let (impl_generics, ty_generics, where_clause) = enum_.generics.split_for_impl();
+    let doc = format!("Boxed handle to a [`{kind}`], which keeps results of it small.");
+
+    let code = forwarded.code.then(|| {
+        quote! {
+            impl #impl_generics ::easyerr::ErrorCode for #handle #ty_generics #where_clause {
+                fn code(&self) -> &'static str {
+                    ::easyerr::ErrorCode::code(&*self.0)
+                }
+            }
+        }
+    });
+
+    let http = forwarded.http.then(|| {
+        quote! {
+            impl #impl_generics ::easyerr::HttpError for #handle #ty_generics #where_clause {
+                fn status(&self) -> u16 {
+                    ::easyerr::HttpError::status(&*self.0)
+                }
+
+                fn type_uri(&self) -> Option<&'static str> {
+                    ::easyerr::HttpError::type_uri(&*self.0)
+                }
+
+                fn title(&self) -> Option<&'static str> {
+                    ::easyerr::HttpError::title(&*self.0)
+                }
+
+                fn visit_extensions(&self, visitor: &mut dyn ::easyerr::FieldVisitor) {
+                    ::easyerr::HttpError::visit_extensions(&*self.0, visitor);
+                }
+            }
+        }
+    });
+
+    let exit_code = quote! {
+        impl #impl_generics ::easyerr::ExitCodeError for #handle #ty_generics #where_clause {
+            fn exit_code(&self) -> u8 {
+                ::easyerr::ExitCodeError::exit_code(&*self.0)
+            }
+        }
+    };
+
+    let raw = forwarded.raw.then(|| {
+        quote! {
+            impl #impl_generics ::easyerr::RawError for #handle #ty_generics #where_clause {
+                fn as_raw(&self) -> i32 {
+                    ::easyerr::RawError::as_raw(&*self.0)
+                }
+
+                fn from_raw(raw: i32) -> Option<Self> {
+                    <#kind #ty_generics as ::easyerr::RawError>::from_raw(raw).map(Self::from)
+                }
+
+                fn raw_os_error(&self) -> Option<i32> {
+                    ::easyerr::RawError::raw_os_error(&*self.0)
+                }
+            }
+        }
+    });
+
+    let io = forwarded.io.then(|| {
+        let mut where_clause = where_clause
+            .cloned()
+            .unwrap_or_else(|| syn::parse_quote!(where));
+        where_clause.predicates.push(syn::parse_quote! {
+            #kind #ty_generics: ::core::marker::Send + ::core::marker::Sync + 'static
+        });
+
+        quote! {
+            impl #impl_generics ::core::convert::From<#handle #ty_generics> for ::std::io::Error
+            #where_clause
+            {
+                fn from(err: #handle #ty_generics) -> Self {
+                    ::std::io::Error::from(*err.0)
+                }
+            }
+        }
+    });
+
+    let defmt = forwarded.defmt.then(|| {
+        quote! {
+            impl #impl_generics ::easyerr::__private::defmt::Format for #handle #ty_generics #where_clause {
+                fn format(&self, f: ::easyerr::__private::defmt::Formatter<'_>) {
+                    ::easyerr::__private::defmt::Format::format(&*self.0, f);
+                }
+            }
+        }
+    });
+
+    let diagnostic = forwarded.diagnostic.then(|| {
+        quote! {
+            impl #impl_generics ::easyerr::__private::miette::Diagnostic for #handle #ty_generics #where_clause {
+                fn code<'a>(&'a self) -> Option<::easyerr::__private::Box<dyn ::core::fmt::Display + 'a>> {
+                    ::easyerr::__private::miette::Diagnostic::code(&*self.0)
+                }
+
+                fn severity(&self) -> Option<::easyerr::__private::miette::Severity> {
+                    ::easyerr::__private::miette::Diagnostic::severity(&*self.0)
+                }
+
+                fn help<'a>(&'a self) -> Option<::easyerr::__private::Box<dyn ::core::fmt::Display + 'a>> {
+                    ::easyerr::__private::miette::Diagnostic::help(&*self.0)
+                }
+
+                fn url<'a>(&'a self) -> Option<::easyerr::__private::Box<dyn ::core::fmt::Display + 'a>> {
+                    ::easyerr::__private::miette::Diagnostic::url(&*self.0)
+                }
+
+                fn source_code(&self) -> Option<&dyn ::easyerr::__private::miette::SourceCode> {
+                    ::easyerr::__private::miette::Diagnostic::source_code(&*self.0)
+                }
+
+                fn labels(
+                    &self,
+                ) -> Option<
+                    ::easyerr::__private::Box<
+                        dyn Iterator<Item = ::easyerr::__private::miette::LabeledSpan> + '_,
+                    >,
+                > {
+                    ::easyerr::__private::miette::Diagnostic::labels(&*self.0)
+                }
+
+                fn related<'a>(
+                    &'a self,
+                ) -> Option<
+                    ::easyerr::__private::Box<
+                        dyn Iterator<Item = &'a dyn ::easyerr::__private::miette::Diagnostic> + 'a,
+                    >,
+                > {
+                    ::easyerr::__private::miette::Diagnostic::related(&*self.0)
+                }
+
+                fn diagnostic_source(&self) -> Option<&dyn ::easyerr::__private::miette::Diagnostic> {
+                    ::easyerr::__private::miette::Diagnostic::diagnostic_source(&*self.0)
+                }
+            }
+        }
+    });
+
+    let serialize = forwarded.serialize.then(|| {
+        quote! {
+            impl #impl_generics ::easyerr::__private::serde::Serialize for #handle #ty_generics #where_clause {
+                fn serialize<__S>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+                where
+                    __S: ::easyerr::__private::serde::Serializer,
+                {
+                    ::easyerr::__private::serde::Serialize::serialize(&*self.0, serializer)
+                }
+            }
+        }
+    });
+
+    let variant_accessors = enum_.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let method = snake_case("is_", ident);
+        let doc = format!("Whether the kind of this error is [`{kind}::{ident}`].");
+        quote! {
+            #[doc = #doc]
+            #vis fn #method(&self) -> bool {
+                ::core::matches!(*self.0, #kind::#ident { .. })
+            }
+        }
+    });
+
+    let log = cfg!(any(feature = "log", feature = "tracing")).then(|| {
+        quote! {
+            fn log(&self, level: ::easyerr::Level) {
+                ::easyerr::LogError::log(&*self.0, level);
+            }
+        }
+    });
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #handle #impl_generics (::easyerr::__private::Box<#kind #ty_generics>) #where_clause;
+
+        impl #impl_generics #handle #ty_generics #where_clause {
+            /// Kind of this error.
+            #vis fn kind(&self) -> &#kind #ty_generics {
+                &self.0
+            }
+
+            /// Kind of this error, mutably.
+            #vis fn kind_mut(&mut self) -> &mut #kind #ty_generics {
+                &mut self.0
+            }
+
+            /// Unboxes the kind of this error.
+            #vis fn into_kind(self) -> #kind #ty_generics {
+                *self.0
+            }
+
+            #(#variant_accessors)*
+        }
+
+        impl #impl_generics ::core::convert::From<#kind #ty_generics> for #handle #ty_generics #where_clause {
+            fn from(kind: #kind #ty_generics) -> Self {
+                Self(::easyerr::__private::Box::new(kind))
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Display for #handle #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&*self.0, f)
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Debug for #handle #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Debug::fmt(&*self.0, f)
+            }
+        }
+
+        impl #impl_generics ::core::error::Error for #handle #ty_generics #where_clause {
+            fn source(&self) -> Option<&(dyn ::core::error::Error + 'static)> {
+                ::core::error::Error::source(&*self.0)
+            }
+        }
+
+        impl #impl_generics ::easyerr::LogError for #handle #ty_generics #where_clause {
+            fn level(&self) -> ::easyerr::Level {
+                ::easyerr::LogError::level(&*self.0)
+            }
+
+            #log
+        }
+
+        impl #impl_generics ::easyerr::ErrorFields for #handle #ty_generics #where_clause {
+            fn visit_fields(&self, visitor: &mut dyn ::easyerr::FieldVisitor) {
+                ::easyerr::ErrorFields::visit_fields(&*self.0, visitor);
+            }
+        }
+
+        impl #impl_generics ::easyerr::Hints for #handle #ty_generics #where_clause {
+            fn visit_hints(
+                &self,
+                visit: &mut dyn FnMut(::easyerr::HintKind, &dyn ::core::fmt::Display),
+            ) {
+                ::easyerr::Hints::visit_hints(&*self.0, visit);
+            }
+
+            fn source_hints(&self) -> Option<&dyn ::easyerr::Hints> {
+                ::easyerr::Hints::source_hints(&*self.0)
+            }
+        }
+
+        #code
+        #http
+        #exit_code
+        #raw
+        #io
+        #defmt
+        #diagnostic
+        #serialize
+    })
+}
+
+/// Generates a compile-time assertion that the type `ty` is at most `max_size` bytes.
+pub fn generate_size_assertion(
+    ty: &Ident,
+    generics: &Generics,
+    max_size: &LitInt,
+) -> syn::Result<TokenStream> {
+    if !generics.params.is_empty() {
+        return Err(Error::new(
+            max_size.span(),
+            "`max_size` is not supported on generic types",
+        ));
+    }
+
+    let message = format!(
+        "`{ty}` is larger than its `max_size` of {} bytes",
+        max_size.base10_digits()
+    );
+    Ok(quote! {
+        const _: () = ::core::assert!(::core::mem::size_of::<#ty>() <= #max_size, #message);
+    })
+}
@@ -0,0 +1,57 @@
+use crate::{attr::Message, unless_minimal, Shape};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+/// Generates the catalog entries of the type, one for each of its shapes. Does nothing unless the
+/// `catalog` feature is enabled. Like messages, templates are left out of entries when built with
+/// `--cfg easyerr_minimal`.
+pub fn generate_catalog_entries(ty_ident: &Ident, is_enum: bool, shapes: &[Shape]) -> TokenStream {
+    if !cfg!(feature = "catalog") {
+        return TokenStream::new();
+    }
+
+    let type_name = ty_ident.to_string();
+    let entries = shapes.iter().map(|shape| {
+        let variant = if is_enum {
+            let variant = shape.ident.to_string();
+            quote! { ::core::option::Option::Some(#variant) }
+        } else {
+            quote! { ::core::option::Option::None }
+        };
+
+        let message = match &shape.attrs.message {
+            Some(Message::Format(format_str)) => unless_minimal(
+                &quote! { ::core::option::Option::None },
+                &quote! { ::core::option::Option::Some(#format_str) },
+            ),
+            _ => quote! { ::core::option::Option::None },
+        };
+
+        let code = match &shape.attrs.code {
+            Some(code) => quote! { ::core::option::Option::Some(#code) },
+            None => quote! { ::core::option::Option::None },
+        };
+
+        let docs = &shape.docs;
+        quote! {
+            ::easyerr::__private::inventory::submit! {
+                ::easyerr::catalog::Entry {
+                    module: ::core::module_path!(),
+                    type_name: #type_name,
+                    variant: #variant,
+                    message: #message,
+                    docs: #docs,
+                    code: #code,
+                }
+            }
+        }
+    });
+
+    quote! {
+        #[allow(unexpected_cfgs)]
+        const _: () = {
+            #(#entries)*
+        };
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{source_method, Shape};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::HashSet;
+use syn::{Error, Generics, Ident};
+
+const MISSING_CODE: &str = "missing `code` argument. when a type has error codes, every \
+                            non-transparent struct or variant needs one.";
+
+/// Generates an `ErrorCode` impl for the type, if any of its shapes has a code.
+///
+/// Every shape must either have a code or be transparent, in which case the code of the source is
+/// used. Transparent shapes can have a code of their own too, which they use instead, so that their
+/// source doesn't need to implement `ErrorCode`.
+pub fn generate_error_code_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    shapes: &[Shape],
+) -> syn::Result<Option<TokenStream>> {
+    if shapes.iter().all(|s| s.attrs.code.is_none()) {
+        return Ok(None);
+    }
+
+    let mut seen = HashSet::new();
+    let mut arms = Vec::new();
+    for shape in shapes {
+        let path = &shape.path;
+        let arm = match &shape.attrs.code {
+            Some(code) => {
+                if !seen.insert(code.value()) {
+                    return Err(Error::new(
+                        code.span(),
+                        format!("duplicate error code `{}`", code.value()),
+                    ));
+                }
+
+                quote! { #path { .. } => #code }
+            }
+            None if shape.attrs.is_transparent() => {
+                let code = source_method(shape, &quote! { ::easyerr::ErrorCode::code }, "code")?;
+                quote! { #path { source, .. } => #code }
+            }
+            None => return Err(Error::new(shape.ident.span(), MISSING_CODE)),
+        };
+
+        arms.push(arm);
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(Some(quote! {
+        impl #impl_generics ::easyerr::ErrorCode for #ty_ident #ty_generics #where_clause {
+            fn code(&self) -> &'static str {
+                match self {
+                    #(#arms),*
+                }
+            }
+        }
+    }))
+}
@@ -0,0 +1,74 @@
+use crate::{attr::DebugMode, redact_bindings, Shape};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, Fields, Generics, Ident};
+
+fn generate_debug_arm(shape: &Shape) -> syn::Result<TokenStream> {
+    let pattern = shape.pattern();
+    let name = shape.ident.unraw().to_string();
+    let bindings = shape.bindings();
+    let redactions = redact_bindings(shape.fields)?;
+
+    let body = match shape.fields {
+        Fields::Named(_) => {
+            let names = bindings.iter().map(|b| b.unraw().to_string());
+            quote! {
+                f.debug_struct(#name)
+                    #(.field(#names, &#bindings))*
+                    .finish()
+            }
+        }
+        Fields::Unnamed(_) => quote! {
+            f.debug_tuple(#name)
+                #(.field(&#bindings))*
+                .finish()
+        },
+        Fields::Unit => quote! {
+            f.write_str(#name)
+        },
+    };
+
+    Ok(quote! {
+        #pattern => {
+            #redactions
+            #body
+        }
+    })
+}
+
+/// Generates a `Debug` impl for the type. In [`DebugMode::Fields`], it is equivalent to
+/// `#[derive(Debug)]`, except that redacted fields are hidden. In [`DebugMode::Report`], it
+/// prints the message of the error followed by its chain of sources.
+pub fn generate_debug_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    shapes: &[Shape],
+    mode: DebugMode,
+) -> syn::Result<TokenStream> {
+    let body = match mode {
+        DebugMode::Fields => {
+            let arms = shapes
+                .iter()
+                .map(generate_debug_arm)
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        DebugMode::Report => quote! {
+            ::easyerr::__private::fmt_report(f, self)
+        },
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Debug for #ty_ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
@@ -0,0 +1,295 @@
+use crate::{
+    attr::{ErrorAttrs, Location, Message},
+    field_bindings, is_optional_source, resolve_positional, source_field_of, source_kind, Shape,
+    SourceKind,
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, spanned::Spanned, Error, Generics, Ident, LitStr};
+
+/// Format specs which mean the same to `core::fmt` and to `defmt`. Others, like widths, precisions
+/// or fills, aren't display hints that `defmt` understands.
+const DISPLAY_HINTS: [&str; 9] = ["?", "x", "X", "#x", "#X", "b", "#b", "o", "#o"];
+
+/// Translates a `core::fmt` message into a `defmt` format string and its arguments. Every
+/// `{name[:spec]}` placeholder becomes `{[:spec]}` with `name` as an argument, except for redacted
+/// fields, which are replaced by `<redacted>`. Specs must be one of the [`DISPLAY_HINTS`].
+/// Positional placeholders must have been resolved with [`resolve_positional`] beforehand.
+fn translate_message(
+    message: &LitStr,
+    prefix: &str,
+    redacted: &[String],
+) -> syn::Result<(LitStr, Vec<Ident>)> {
+    let value = message.value();
+    let mut format = escape(prefix);
+    let mut args = Vec::new();
+
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                format.push_str("{{");
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                format.push_str("}}");
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err(Error::new(message.span(), "unclosed placeholder")),
+                    }
+                }
+
+                let (name, spec) = match placeholder.split_once(':') {
+                    Some((name, spec)) => (name, Some(spec)),
+                    None => (placeholder.as_str(), None),
+                };
+
+                let Ok(name) = syn::parse_str::<Ident>(name) else {
+                    return Err(Error::new(
+                        message.span(),
+                        format!("placeholder `{{{placeholder}}}` can't be translated for defmt"),
+                    ));
+                };
+
+                if redacted.contains(&name.unraw().to_string()) {
+                    format.push_str("<redacted>");
+                    continue;
+                }
+
+                match spec {
+                    Some(spec) if !DISPLAY_HINTS.contains(&spec) => {
+                        return Err(Error::new(
+                            message.span(),
+                            format!(
+                                "format spec `{spec}` of `{{{placeholder}}}` can't be translated \
+                                 for defmt, which only supports `?`, `x`, `X`, `b` and `o`, with or \
+                                 without `#` for the latter"
+                            ),
+                        ));
+                    }
+                    Some(spec) => {
+                        format.push_str("{:");
+                        format.push_str(spec);
+                        format.push('}');
+                    }
+                    None => format.push_str("{}"),
+                }
+
+                args.push(format_ident!("{}", name, span = message.span()));
+            }
+            c => format.push(c),
+        }
+    }
+
+    Ok((LitStr::new(&format, message.span()), args))
+}
+
+fn generate_format_arm(shape: &Shape, show_code: bool) -> syn::Result<TokenStream> {
+    let prefix = match &shape.attrs.code {
+        Some(code) if show_code => format!("[{}] ", code.value()),
+        _ => String::new(),
+    };
+
+    match &shape.attrs.message {
+        Some(Message::Format(message)) => {
+            let mut redacted = Vec::new();
+            for (field, binding) in shape.fields.iter().zip(field_bindings(shape.fields)) {
+                if ErrorAttrs::parse(&field.attrs, Location::Field)?
+                    .redact
+                    .is_some()
+                {
+                    redacted.push(binding.unraw().to_string());
+                }
+            }
+
+            let pattern = shape.pattern();
+            let message = resolve_positional(message, shape.fields)?;
+            let (format, args) = translate_message(&message, &prefix, &redacted)?;
+            Ok(quote! {
+                #pattern => ::easyerr::__private::defmt::write!(f, #format #(, #args)*)
+            })
+        }
+        _ => generate_transparent_arm(shape, &prefix),
+    }
+}
+
+/// Arm of a transparent `shape`, which formats its source after `prefix`. Optional sources write
+/// the `fallback` message of the shape instead when they're missing.
+fn generate_transparent_arm(shape: &Shape, prefix: &str) -> syn::Result<TokenStream> {
+    let path = &shape.path;
+    let escaped = escape(prefix);
+    let format = |source: TokenStream| {
+        if prefix.is_empty() {
+            quote! { ::easyerr::__private::defmt::Format::format(#source, f) }
+        } else {
+            let format = format!("{escaped}{{}}");
+            quote! { ::easyerr::__private::defmt::write!(f, #format, #source) }
+        }
+    };
+
+    let Some(field) = source_field_of(shape.fields.iter()) else {
+        let format = format(quote! { source });
+        return Ok(quote! { #path { source, .. } => #format });
+    };
+
+    if source_kind(field)? != SourceKind::Plain {
+        return Err(Error::new(
+            field.ty.span(),
+            "boxed and report sources don't implement `defmt::Format`, so they can't be the \
+             source of a `transparent` struct or variant of a type with `defmt`",
+        ));
+    }
+
+    if !is_optional_source(field) {
+        let format = format(quote! { source });
+        return Ok(quote! { #path { source, .. } => #format });
+    }
+
+    // `Shape::new` makes sure that shapes with an optional source have a fallback
+    let present = format(quote! { source });
+    let missing = shape.attrs.fallback.as_ref().map(|fallback| {
+        LitStr::new(
+            &format!("{escaped}{}", escape(&fallback.value())),
+            fallback.span(),
+        )
+    });
+    Ok(quote! {
+        #path { source: ::core::option::Option::Some(source), .. } => #present,
+        #path { .. } => ::easyerr::__private::defmt::write!(f, #missing)
+    })
+}
+
+/// Escapes the braces of `text`, to use it literally in a defmt format string.
+fn escape(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+/// Generates a `defmt::Format` impl for the type, if it has `#[error(defmt)]`.
+pub fn generate_defmt_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    attrs: &ErrorAttrs,
+    shapes: &[Shape],
+) -> syn::Result<TokenStream> {
+    if !attrs.defmt {
+        return Ok(TokenStream::new());
+    }
+
+    if !cfg!(feature = "defmt") {
+        return Err(Error::new(
+            ty_ident.span(),
+            "`defmt` requires the `defmt` feature of easyerr",
+        ));
+    }
+
+    let arms = shapes
+        .iter()
+        .map(|shape| generate_format_arm(shape, attrs.show_code))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::easyerr::__private::defmt::Format for #ty_ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn format(&self, f: ::easyerr::__private::defmt::Formatter<'_>) {
+                match self {
+                    #(#arms),*
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::translate_message;
+    use crate::resolve_positional;
+    use syn::{parse_quote, Fields, LitStr};
+
+    fn try_translate(
+        message: &str,
+        prefix: &str,
+        redacted: &[&str],
+    ) -> syn::Result<(LitStr, Vec<syn::Ident>)> {
+        let fields: Fields = Fields::Unnamed(parse_quote!((u8, u32)));
+        let message = resolve_positional(
+            &LitStr::new(message, proc_macro2::Span::call_site()),
+            &fields,
+        )?;
+        let redacted = redacted.iter().map(ToString::to_string).collect::<Vec<_>>();
+        translate_message(&message, prefix, &redacted)
+    }
+
+    fn translate(message: &str, prefix: &str, redacted: &[&str]) -> (String, Vec<String>) {
+        let (format, args) = try_translate(message, prefix, redacted).unwrap();
+        (
+            format.value(),
+            args.iter().map(ToString::to_string).collect(),
+        )
+    }
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(
+            translate("sensor {id} read {value:#x}", "", &[]),
+            (
+                "sensor {} read {:#x}".into(),
+                vec!["id".into(), "value".into()]
+            )
+        );
+        assert_eq!(
+            translate("bad state {state:?}", "", &[]),
+            ("bad state {:?}".into(), vec!["state".into()])
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            translate("{{literal}} {x} }}", "[{E1}] ", &[]),
+            ("[{{E1}}] {{literal}} {} }}".into(), vec!["x".into()])
+        );
+    }
+
+    #[test]
+    fn redacted() {
+        assert_eq!(
+            translate("key {key} of {owner}", "", &["key"]),
+            ("key <redacted> of {}".into(), vec!["owner".into()])
+        );
+    }
+
+    #[test]
+    fn positional() {
+        assert_eq!(
+            translate("{} then {:?} then {0}", "", &[]),
+            (
+                "{} then {:?} then {}".into(),
+                vec!["f0".into(), "f1".into(), "f0".into()]
+            )
+        );
+        assert_eq!(
+            translate("only {f1}", "", &["f1"]),
+            ("only <redacted>".into(), vec![])
+        );
+    }
+
+    #[test]
+    fn unsupported_specs() {
+        for message in [
+            "{f0:>4}", "{f0:.2}", "{f0:*^9}", "{f0:08x}", "{f0:#?}", "{:e}",
+        ] {
+            let err = try_translate(message, "", &[]).unwrap_err();
+            assert!(
+                err.to_string().contains("can't be translated for defmt"),
+                "{message}: {err}"
+            );
+        }
+    }
+}
@@ -1,51 +1,120 @@
-use crate::{extract_source_field, generics_required_by_type, source_field_of, ErrorAttrArg};
+use crate::{
+    attr::{ErrorAttrs, Location, Message},
+    boxed::{generate_handle, generate_size_assertion, handle_ident, Forwarded},
+    catalog::generate_catalog_entries,
+    code::generate_error_code_impl,
+    code_prefix, context_source,
+    debug::generate_debug_impl,
+    defmt::generate_defmt_impl,
+    exit::generate_exit_code_impl,
+    extract_source_field,
+    fields::generate_error_fields_impl,
+    generate_leaf_selector, generics_required_by_type,
+    hints::generate_hints_impl,
+    http::generate_http_error_impl,
+    io::generate_io_error_impl,
+    is_optional_source,
+    log::generate_log_error_impl,
+    miette::generate_diagnostic_impl,
+    raw::{discriminants, generate_raw_error_impl},
+    ser::generate_serialize_impl,
+    source_as_dyn, source_field_of, write_message, write_transparent, Shape,
+};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote, quote_spanned};
 use std::collections::HashSet;
 use syn::{
-    parse_quote, spanned::Spanned, Error, Fields, FieldsNamed, Generics, ItemEnum, Type, Variant,
-    Visibility,
+    parse_quote, spanned::Spanned, Error, Field, Fields, FieldsNamed, Generics, Ident, ItemEnum,
+    Variant, Visibility,
 };
 
+/// The error created by the contexts of an enum: the enum itself, or its handle for `boxed` enums.
+struct ContextTarget<'a> {
+    enum_: &'a ItemEnum,
+    handle: Option<Ident>,
+}
+
+impl ContextTarget<'_> {
+    /// Type of the created error.
+    fn ty(&self) -> TokenStream {
+        let ident = self.handle.as_ref().unwrap_or(&self.enum_.ident);
+        let (_, ty_generics, _) = self.enum_.generics.split_for_impl();
+        quote! { #ident #ty_generics }
+    }
+
+    /// Turns `variant`, an expression creating a variant of the enum, into the created error.
+    fn create(&self, variant: TokenStream) -> TokenStream {
+        match &self.handle {
+            Some(handle) => quote! { #handle::from(#variant) },
+            None => variant,
+        }
+    }
+}
+
 fn generate_empty_named_variant_ctx(
-    enum_: &ItemEnum,
+    target: &ContextTarget,
     variant: &Variant,
-    source_field_ty: &Type,
-) -> TokenStream {
+    source_field: &Field,
+) -> Result<TokenStream, Error> {
+    let enum_ = target.enum_;
     let ctx_ident = &variant.ident;
     let ty_ident = &enum_.ident;
-    let (ty_impl_generics, ty_ty_generics, ty_where_clause) = enum_.generics.split_for_impl();
+    let err_ty = target.ty();
+    let source = context_source(source_field, &enum_.generics)?;
+    let (impl_generics, _, where_clause) = source.generics.split_for_impl();
+    let source_ty = &source.ty;
+    let store = &source.store;
 
     let struct_def = quote! {
         pub(super) struct #ctx_ident;
     };
 
+    let create = target.create(quote! {
+        #ty_ident::#ctx_ident {
+            source: #store,
+        }
+    });
+
     let ctx_impl = quote! {
-        impl #ty_impl_generics ::easyerr::ErrorContext for #ctx_ident #ty_where_clause {
-            type Source = #source_field_ty;
-            type Err = #ty_ident #ty_ty_generics;
+        impl #impl_generics ::easyerr::ErrorContext<#source_ty> for #ctx_ident #where_clause {
+            type Err = #err_ty;
 
-            #[inline(always)]
-            fn add_to_source(self, source: Self::Source) -> #ty_ident #ty_ty_generics {
-                #ty_ident::#ctx_ident {
-                    source,
-                }
+            #[cold]
+            fn add_to_source(self, source: #source_ty) -> #err_ty {
+                #create
             }
         }
     };
 
-    quote! {
+    let leaf_selector = is_optional_source(source_field).then(|| {
+        let leaf_ident = format_ident!("{}Leaf", ctx_ident);
+        generate_leaf_selector(
+            &quote! { pub(super) struct #leaf_ident; },
+            &quote! { #leaf_ident },
+            &enum_.generics,
+            &err_ty,
+            &target.create(quote! {
+                #ty_ident::#ctx_ident {
+                    source: ::core::option::Option::None,
+                }
+            }),
+        )
+    });
+
+    Ok(quote! {
         #struct_def
         #ctx_impl
-    }
+        #leaf_selector
+    })
 }
 
 fn generate_named_variant_ctx(
-    enum_: &ItemEnum,
+    target: &ContextTarget,
     variant: &Variant,
     fields: &FieldsNamed,
 ) -> Result<TokenStream, Error> {
-    let (mut ctx_fields, Some(source_field_ty)) = extract_source_field(fields.named.iter()) else {
+    let enum_ = target.enum_;
+    let (mut ctx_fields, Some(source_field)) = extract_source_field(fields.named.iter()) else {
         return Err(Error::new(
             variant.span(),
             "can't generate context for variant without source",
@@ -53,16 +122,12 @@ fn generate_named_variant_ctx(
     };
 
     if ctx_fields.is_empty() {
-        return Ok(generate_empty_named_variant_ctx(
-            enum_,
-            variant,
-            &source_field_ty,
-        ));
+        return generate_empty_named_variant_ctx(target, variant, &source_field);
     }
 
-    ctx_fields
-        .iter_mut()
-        .for_each(|f| f.vis = Visibility::Public(syn::token::Pub { span: f.vis.span() }));
+    for f in &mut ctx_fields {
+        f.vis = Visibility::Public(syn::token::Pub { span: f.vis.span() });
+    }
 
     let used_generics = fields
         .named
@@ -76,7 +141,11 @@ fn generate_named_variant_ctx(
     };
 
     let ty_ident = &enum_.ident;
-    let (ty_impl_generics, ty_ty_generics, ty_where_clause) = enum_.generics.split_for_impl();
+    let err_ty = target.ty();
+    let source = context_source(&source_field, &enum_.generics)?;
+    let (impl_generics, _, where_clause) = source.generics.split_for_impl();
+    let source_ty = &source.ty;
+    let store = &source.store;
 
     let ctx_ident = &variant.ident;
     let (ctx_impl_generics, ctx_ty_generics, ctx_where_clause) = used_generics.split_for_impl();
@@ -87,111 +156,137 @@ fn generate_named_variant_ctx(
         }
     };
 
-    let ctx_fields_extract = ctx_fields.iter().map(|f| {
-        let f_name = &f.ident;
-        quote! {
-            #f_name: self.#f_name
+    let ctx_fields_extract = ctx_fields
+        .iter()
+        .map(|f| {
+            let f_name = &f.ident;
+            quote! {
+                #f_name: self.#f_name
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let create = target.create(quote! {
+        #ty_ident::#ctx_ident {
+            source: #store,
+            #(#ctx_fields_extract),*
         }
     });
 
     let ctx_impl = quote! {
-        impl #ty_impl_generics ::easyerr::ErrorContext for #ctx_ident #ctx_ty_generics #ty_where_clause {
-            type Source = #source_field_ty;
-            type Err = #ty_ident #ty_ty_generics;
+        impl #impl_generics ::easyerr::ErrorContext<#source_ty> for #ctx_ident #ctx_ty_generics #where_clause {
+            type Err = #err_ty;
 
-            #[inline(always)]
-            fn add_to_source(self, source: Self::Source) -> #ty_ident #ty_ty_generics {
-                #ty_ident::#ctx_ident {
-                    source,
-                    #(#ctx_fields_extract),*
-                }
+            #[cold]
+            fn add_to_source(self, source: #source_ty) -> #err_ty {
+                #create
             }
         }
     };
 
+    let leaf_selector = is_optional_source(&source_field).then(|| {
+        let leaf_ident = format_ident!("{}Leaf", ctx_ident);
+        generate_leaf_selector(
+            &quote! {
+                pub(super) struct #leaf_ident #ctx_impl_generics #ctx_where_clause {
+                    #(#ctx_fields),*
+                }
+            },
+            &quote! { #leaf_ident #ctx_ty_generics },
+            &enum_.generics,
+            &err_ty,
+            &target.create(quote! {
+                #ty_ident::#ctx_ident {
+                    source: ::core::option::Option::None,
+                    #(#ctx_fields_extract),*
+                }
+            }),
+        )
+    });
+
     Ok(quote! {
         #struct_def
         #ctx_impl
+        #leaf_selector
     })
 }
 
-fn generate_variant_ctx(enum_: &ItemEnum, variant: &Variant) -> Result<Option<TokenStream>, Error> {
+fn generate_variant_ctx(
+    target: &ContextTarget,
+    variant: &Variant,
+) -> Result<Option<TokenStream>, Error> {
     match &variant.fields {
         Fields::Named(f) => source_field_of(f.named.iter())
             .is_some()
-            .then(|| generate_named_variant_ctx(enum_, variant, f))
+            .then(|| generate_named_variant_ctx(target, variant, f))
             .transpose(),
         Fields::Unnamed(_) => Ok(None),
         Fields::Unit => Ok(None),
     }
 }
 
-fn generate_variant_display_arm(variant: &Variant) -> Result<TokenStream, Error> {
+fn generate_variant_display_arm(
+    enum_ident: &Ident,
+    variant: &Variant,
+    shape: &Shape,
+    attrs: &ErrorAttrs,
+    discriminant: Option<&(TokenStream, Option<i64>)>,
+) -> Result<TokenStream, Error> {
     let variant_ident = &variant.ident;
-    let error_attr = variant
-        .attrs
-        .iter()
-        .find(|a| a.path().is_ident("error"))
-        .ok_or(Error::new(
-            variant.span(),
-            "variant is missing #[error(...)] attribute",
-        ))?;
-
-    let error_attr_arg = error_attr.parse_args::<ErrorAttrArg>()?;
-    let display = match error_attr_arg {
-        ErrorAttrArg::Str(variant_display_str) => match &variant.fields {
-            Fields::Named(f) => {
-                let fields = f.named.iter().map(|f| &f.ident);
-                quote! {
-                    Self::#variant_ident { #(#fields),* } => {
-                        write!(f, #variant_display_str)?;
-                    }
-                }
-            }
-            Fields::Unnamed(f) => {
-                let fields = f
-                    .unnamed
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| format_ident!("f{i}"));
-                quote! {
-                    Self::#variant_ident(#(#fields),*) => {
-                        write!(f, #variant_display_str)?;
-                    }
-                }
-            }
-            Fields::Unit => {
-                quote! {
-                    Self::#variant_ident => {
-                        write!(f, #variant_display_str)?;
-                    }
+    let prefix = code_prefix(shape, attrs.show_code);
+    let display = match &shape.attrs.message {
+        Some(Message::Format(variant_display_str)) => {
+            let pattern = shape.pattern();
+            let name = format!("{}::{}", enum_ident, variant_ident);
+            let message = write_message(shape, &name, variant_display_str, &prefix, discriminant)?;
+            quote! {
+                #pattern => {
+                    #message
                 }
             }
-        },
-        ErrorAttrArg::Transparent => {
+        }
+        Some(Message::Transparent(span)) => {
             if source_field_of(variant.fields.iter()).is_none() {
                 return Err(Error::new(
-                    error_attr.span(),
+                    *span,
                     "can't use `transparent` display on a variant with no source field",
                 ));
             }
 
+            let name = format!("{}::{}", enum_ident, variant_ident);
+            let message = write_transparent(shape, &name, &quote! { source });
             quote! {
                 Self::#variant_ident { source, .. } => {
-                    source.fmt(f)?;
+                    #prefix
+                    #message
                 }
             }
         }
+        None => {
+            return Err(Error::new(
+                variant.span(),
+                "variant is missing #[error(...)] attribute",
+            ))
+        }
     };
 
     Ok(display)
 }
 
-fn generate_enum_display_impl(enum_: &ItemEnum) -> Result<TokenStream, Error> {
+fn generate_enum_display_impl(
+    enum_: &ItemEnum,
+    shapes: &[Shape],
+    attrs: &ErrorAttrs,
+    discriminants: &[(TokenStream, Option<i64>)],
+) -> Result<TokenStream, Error> {
     let match_arms: Result<Vec<_>, _> = enum_
         .variants
         .iter()
-        .map(generate_variant_display_arm)
+        .zip(shapes)
+        .enumerate()
+        .map(|(i, (variant, shape))| {
+            generate_variant_display_arm(&enum_.ident, variant, shape, attrs, discriminants.get(i))
+        })
         .collect();
     let match_arms = match_arms?;
 
@@ -200,44 +295,40 @@ fn generate_enum_display_impl(enum_: &ItemEnum) -> Result<TokenStream, Error> {
 
     Ok(quote! {
         impl #impl_generics core::fmt::Display for #enum_ident #ty_generics #where_clause {
-            #[allow(unused_variables)]
+            #[allow(unused_variables, unexpected_cfgs)]
             fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
                 match self {
                     #(#match_arms),*
                 }
 
+                if f.alternate() {
+                    ::easyerr::__private::fmt_hints(f, self, ::core::error::Error::source(self))?;
+                }
+
                 Ok(())
             }
         }
     })
 }
 
-fn generate_variant_error_arm(variant: &Variant) -> Result<TokenStream, Error> {
+fn generate_variant_error_arm(variant: &Variant, shape: &Shape) -> Result<TokenStream, Error> {
     let variant_ident = &variant.ident;
-    let display = match &variant.fields {
+    Ok(match &variant.fields {
         Fields::Named(f) => {
             if let Some(f) = source_field_of(f.named.iter()) {
-                let error_attr = variant
-                    .attrs
-                    .iter()
-                    .find(|a| a.path().is_ident("error"))
-                    .ok_or(Error::new(
-                        variant.span(),
-                        "variant is missing #[error(...)] attribute",
-                    ))?;
-                let error_attr_arg = error_attr.parse_args::<ErrorAttrArg>()?;
-                if error_attr_arg == ErrorAttrArg::Transparent {
+                let source = source_as_dyn(f, quote! { source })?;
+                if shape.attrs.is_transparent() {
                     quote_spanned! {
                         f.ty.span() =>
                         Self::#variant_ident { source, .. } => {
-                            source.source()
+                            #source.and_then(::core::error::Error::source)
                         }
                     }
                 } else {
                     quote_spanned! {
                         f.ty.span() =>
                             Self::#variant_ident { source, .. } => {
-                                Some(source)
+                                #source
                             }
                     }
                 }
@@ -263,20 +354,18 @@ fn generate_variant_error_arm(variant: &Variant) -> Result<TokenStream, Error> {
                 }
             }
         }
-    };
-
-    Ok(display)
+    })
 }
 
-fn generate_enum_error_impl(enum_: &ItemEnum) -> Result<TokenStream, Error> {
+fn generate_enum_error_impl(enum_: &ItemEnum, shapes: &[Shape]) -> Result<TokenStream, Error> {
     let ty_ident = &enum_.ident;
     let (impl_generics, ty_generics, where_clause) = enum_.generics.split_for_impl();
-    let match_arms: Result<Vec<_>, _> = enum_
+    let match_arms = enum_
         .variants
         .iter()
-        .map(generate_variant_error_arm)
-        .collect();
-    let match_arms = match_arms?;
+        .zip(shapes)
+        .map(|(variant, shape)| generate_variant_error_arm(variant, shape))
+        .collect::<Result<Vec<_>, _>>()?;
 
     Ok(quote! {
         impl #impl_generics ::core::error::Error for #ty_ident #ty_generics #where_clause {
@@ -290,16 +379,106 @@ fn generate_enum_error_impl(enum_: &ItemEnum) -> Result<TokenStream, Error> {
 }
 
 pub fn derive_err_enum(enum_: &ItemEnum) -> Result<TokenStream, Error> {
+    let attrs = ErrorAttrs::parse(&enum_.attrs, Location::Enum)?;
+    let shapes = enum_
+        .variants
+        .iter()
+        .map(|v| {
+            let variant_ident = &v.ident;
+            Shape::new(
+                quote! { Self::#variant_ident },
+                variant_ident,
+                &v.fields,
+                &v.attrs,
+                Location::Variant,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let target = ContextTarget {
+        enum_,
+        handle: attrs
+            .boxed
+            .then(|| handle_ident(&enum_.ident))
+            .transpose()?,
+    };
     let contexts = enum_
         .variants
         .iter()
-        .map(|v| generate_variant_ctx(enum_, v))
+        .map(|v| generate_variant_ctx(&target, v))
         .collect::<Result<Vec<_>, _>>()?;
-    let display_impl = generate_enum_display_impl(enum_)?;
-    let error_impl = generate_enum_error_impl(enum_)?;
+    let explicit_discriminants = enum_
+        .variants
+        .iter()
+        .map(|v| v.discriminant.as_ref().map(|(_, d)| d))
+        .collect::<Vec<_>>();
+    let display_impl = generate_enum_display_impl(
+        enum_,
+        &shapes,
+        &attrs,
+        &discriminants(&explicit_discriminants),
+    )?;
+    let error_impl = generate_enum_error_impl(enum_, &shapes)?;
+    let code_impl = generate_error_code_impl(&enum_.ident, &enum_.generics, &shapes)?;
+    if attrs.show_code && code_impl.is_none() {
+        return Err(Error::new(
+            enum_.ident.span(),
+            "`show_code` requires at least one variant to have a `code`",
+        ));
+    }
 
+    let exit_code_impl = generate_exit_code_impl(&enum_.ident, &enum_.generics, &shapes);
+    let raw_impl = generate_raw_error_impl(
+        &enum_.ident,
+        &enum_.generics,
+        &shapes,
+        &explicit_discriminants,
+    )?;
+    let io_impl = generate_io_error_impl(&enum_.ident, &enum_.generics, &attrs, &shapes, true)?;
+    let hints_impl = generate_hints_impl(&enum_.ident, &enum_.generics, &shapes)?;
+    let fields_impl = generate_error_fields_impl(&enum_.ident, &enum_.generics, &shapes)?;
+    let log_impl = generate_log_error_impl(&enum_.ident, &enum_.generics, &shapes)?;
+    let http_impl = generate_http_error_impl(&enum_.ident, &enum_.generics, &shapes)?;
+    let diagnostic_impl = generate_diagnostic_impl(&enum_.ident, &enum_.generics, &attrs, &shapes)?;
+    let defmt_impl = generate_defmt_impl(&enum_.ident, &enum_.generics, &attrs, &shapes)?;
+    let serialize_impl =
+        generate_serialize_impl(&enum_.ident, &enum_.generics, &attrs, &shapes, true)?;
+    // the entries of a boxed enum are listed under its handle, which is the public error type
+    let catalog_entries = generate_catalog_entries(
+        target.handle.as_ref().unwrap_or(&enum_.ident),
+        true,
+        &shapes,
+    );
+    let debug_impl = attrs
+        .debug
+        .map(|mode| generate_debug_impl(&enum_.ident, &enum_.generics, &shapes, mode))
+        .transpose()?;
+    let handle = target
+        .handle
+        .as_ref()
+        .map(|handle| {
+            let forwarded = Forwarded {
+                code: code_impl.is_some(),
+                http: http_impl.is_some(),
+                raw: raw_impl.is_some(),
+                io: !io_impl.is_empty(),
+                defmt: !defmt_impl.is_empty(),
+                diagnostic: !diagnostic_impl.is_empty(),
+                serialize: !serialize_impl.is_empty(),
+            };
+            generate_handle(enum_, handle, &forwarded)
+        })
+        .transpose()?;
+    let size_assertion = attrs
+        .max_size
+        .as_ref()
+        .map(|max_size| {
+            let ty = target.handle.as_ref().unwrap_or(&enum_.ident);
+            generate_size_assertion(ty, &enum_.generics, max_size)
+        })
+        .transpose()?;
     let module = (!contexts.is_empty()).then(|| {
-        let enum_ident_str = enum_.ident.to_string();
+        let enum_ident_str = target.handle.as_ref().unwrap_or(&enum_.ident).to_string();
         let module_ident_str = enum_ident_str
             .strip_suffix("Error")
             .unwrap_or(&enum_ident_str);
@@ -316,7 +495,22 @@ pub fn derive_err_enum(enum_: &ItemEnum) -> Result<TokenStream, Error> {
 
     Ok(quote! {
         #module
+        #handle
+        #size_assertion
         #display_impl
         #error_impl
+        #code_impl
+        #http_impl
+        #log_impl
+        #fields_impl
+        #hints_impl
+        #exit_code_impl
+        #raw_impl
+        #io_impl
+        #debug_impl
+        #defmt_impl
+        #diagnostic_impl
+        #catalog_entries
+        #serialize_impl
     })
 }
@@ -0,0 +1,42 @@
+use crate::{source_as_trait, Shape};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Generics, Ident};
+
+/// Generates an `ExitCodeError` impl for the type.
+///
+/// Transparent shapes without an exit code use the exit code of their source, if it implements
+/// `ExitCodeError`, and other shapes without one use `1`.
+pub fn generate_exit_code_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    shapes: &[Shape],
+) -> TokenStream {
+    let arms = shapes.iter().map(|shape| {
+        let path = &shape.path;
+        match &shape.attrs.exit_code {
+            Some(code) => quote! { #path { .. } => #code },
+            None if shape.attrs.is_transparent() => {
+                let source = source_as_trait(shape, "ExitCodeRef", "exit_code_error");
+                quote! {
+                    #path { source, .. } => #source.map_or(1, ::easyerr::ExitCodeError::exit_code)
+                }
+            }
+            None => quote! { #path { .. } => 1 },
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::easyerr::ExitCodeError for #ty_ident #ty_generics #where_clause {
+            fn exit_code(&self) -> u8 {
+                #[allow(unused_imports)]
+                use ::easyerr::__private::{NoExitCode as _, ViaExitCode as _};
+
+                match self {
+                    #(#arms),*
+                }
+            }
+        }
+    }
+}
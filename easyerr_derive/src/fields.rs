@@ -0,0 +1,81 @@
+use crate::{exposed_fields, ExposedField, Shape};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Generics, Ident};
+
+/// Rebinds each of the given exposed fields to an `easyerr::__private::Field`, which records it
+/// as a primitive value or through its `Display` or `Debug` impl. Requires [`field_traits`] to be in
+/// scope.
+pub fn bind_field_values(fields: &[ExposedField]) -> TokenStream {
+    let values = fields.iter().map(|field| {
+        let ExposedField {
+            binding, redact, ..
+        } = field;
+
+        let redact = redact.map(|mode| {
+            quote! {
+                let #binding = &::easyerr::Redacted::scoped(#binding, #mode);
+            }
+        });
+
+        quote! {
+            #redact
+            let #binding = (&&&&&::easyerr::__private::FieldRef(#binding)).field();
+        }
+    });
+
+    quote! { #(#values)* }
+}
+
+/// Imports the traits used by [`bind_field_values`].
+pub fn field_traits() -> TokenStream {
+    quote! {
+        #[allow(unused_imports)]
+        use ::easyerr::__private::{
+            Opaque as _, ViaDebug as _, ViaDisplay as _, ViaPrimitive as _, ViaPrintable as _,
+        };
+    }
+}
+
+fn generate_visit_arm(shape: &Shape) -> syn::Result<TokenStream> {
+    let pattern = shape.pattern();
+    let fields = exposed_fields(shape.fields)?;
+    let values = bind_field_values(&fields);
+    let records = fields.iter().map(|ExposedField { key, binding, .. }| {
+        quote! { #binding.record(#key, __visitor); }
+    });
+
+    Ok(quote! {
+        #pattern => {
+            #values
+            #(#records)*
+        }
+    })
+}
+
+/// Generates an `ErrorFields` impl for the type.
+pub fn generate_error_fields_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    shapes: &[Shape],
+) -> syn::Result<TokenStream> {
+    let arms = shapes
+        .iter()
+        .map(generate_visit_arm)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let traits = field_traits();
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::easyerr::ErrorFields for #ty_ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn visit_fields(&self, __visitor: &mut dyn ::easyerr::FieldVisitor) {
+                #traits
+
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,124 @@
+use crate::{redact_bindings, resolve_positional, source_as_trait, source_field_of, Shape};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Generics, Ident, LitStr};
+
+fn has_hints(shape: &Shape) -> bool {
+    shape.attrs.help.is_some() || !shape.attrs.notes.is_empty()
+}
+
+/// Expression evaluating to the hints of the `source` binding of `shape`, if any.
+fn source_hints(shape: &Shape) -> TokenStream {
+    source_as_trait(shape, "HintsRef", "hints")
+}
+
+fn generate_visit_arm(shape: &Shape) -> syn::Result<TokenStream> {
+    let path = &shape.path;
+    if shape.attrs.is_transparent() && !has_hints(shape) {
+        let hints = source_hints(shape);
+        return Ok(quote! {
+            #path { source, .. } => {
+                if let Some(hints) = #hints {
+                    hints.visit_hints(visit);
+                }
+            }
+        });
+    }
+
+    if !has_hints(shape) {
+        return Ok(quote! { #path { .. } => {} });
+    }
+
+    // hints are formatted like messages, with the formatter deciding whether to reveal redacted
+    // fields
+    let redactions = redact_bindings(shape.fields)?;
+    let hint = |hint: &LitStr| -> syn::Result<TokenStream> {
+        let hint = resolve_positional(hint, shape.fields)?;
+        Ok(quote! {
+            &::easyerr::__private::DisplayFn(|f: &mut ::core::fmt::Formatter<'_>| {
+                #redactions
+                write!(f, #hint)
+            })
+        })
+    };
+    let help = shape
+        .attrs
+        .help
+        .iter()
+        .map(hint)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let notes = shape
+        .attrs
+        .notes
+        .iter()
+        .map(hint)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let pattern = shape.pattern();
+    Ok(quote! {
+        #pattern => {
+            // hints are left out of the binary just like messages
+            #[cfg(not(easyerr_minimal))]
+            {
+                #(visit(::easyerr::HintKind::Help, #help);)*
+                #(visit(::easyerr::HintKind::Note, #notes);)*
+            }
+        }
+    })
+}
+
+fn generate_source_hints_arm(shape: &Shape) -> TokenStream {
+    let path = &shape.path;
+    if source_field_of(shape.fields.iter()).is_none() {
+        return quote! { #path { .. } => None };
+    }
+
+    let hints = source_hints(shape);
+    if shape.attrs.is_transparent() {
+        // the source of a transparent error is the source of its source
+        quote! {
+            #path { source, .. } => #hints.and_then(::easyerr::Hints::source_hints)
+        }
+    } else {
+        quote! { #path { source, .. } => #hints }
+    }
+}
+
+/// Generates a `Hints` impl for the type.
+pub fn generate_hints_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    shapes: &[Shape],
+) -> syn::Result<TokenStream> {
+    let visit_arms = shapes
+        .iter()
+        .map(generate_visit_arm)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let source_hints_arms = shapes.iter().map(generate_source_hints_arm);
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::easyerr::Hints for #ty_ident #ty_generics #where_clause {
+            #[allow(unused_variables, unexpected_cfgs)]
+            fn visit_hints(
+                &self,
+                visit: &mut dyn FnMut(::easyerr::HintKind, &dyn ::core::fmt::Display),
+            ) {
+                #[allow(unused_imports)]
+                use ::easyerr::__private::{NoHints as _, ViaHints as _};
+
+                match self {
+                    #(#visit_arms)*
+                }
+            }
+
+            fn source_hints(&self) -> Option<&dyn ::easyerr::Hints> {
+                #[allow(unused_imports)]
+                use ::easyerr::__private::{NoHints as _, ViaHints as _};
+
+                match self {
+                    #(#source_hints_arms),*
+                }
+            }
+        }
+    })
+}
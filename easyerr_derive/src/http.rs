@@ -0,0 +1,166 @@
+use crate::{
+    exposed_fields,
+    fields::{bind_field_values, field_traits},
+    source_as_trait, ExposedField, Shape,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Generics, Ident};
+
+/// Whether the HTTP error impl of `shape` is delegated to its source, which is the case for
+/// `transparent` shapes without any HTTP arguments. Sources which don't implement `HttpError` get
+/// the defaults: `500`, no type, no title and no extensions.
+fn delegates(shape: &Shape) -> bool {
+    let attrs = &shape.attrs;
+    attrs.is_transparent()
+        && attrs.status.is_none()
+        && attrs.type_uri.is_none()
+        && attrs.title.is_none()
+}
+
+/// Members of problem details which extension members must not shadow.
+const RESERVED_MEMBERS: [&str; 4] = ["type", "status", "title", "detail"];
+
+fn generate_extensions_arm(shape: &Shape) -> syn::Result<TokenStream> {
+    let path = &shape.path;
+    if delegates(shape) {
+        let source = source_as_trait(shape, "HttpRef", "http_error");
+        return Ok(quote! {
+            #path { source, .. } => {
+                if let Some(source) = #source {
+                    source.visit_extensions(__visitor);
+                }
+            }
+        });
+    }
+
+    let fields = exposed_fields(shape.fields)?;
+    if let Some(field) = fields
+        .iter()
+        .find(|f| RESERVED_MEMBERS.contains(&f.key.as_str()))
+    {
+        return Err(syn::Error::new(
+            field.binding.span(),
+            format!(
+                "`{}` collides with a problem details member; rename it or mark it `#[error(skip)]`",
+                field.key
+            ),
+        ));
+    }
+
+    let pattern = shape.pattern();
+    let values = bind_field_values(&fields);
+    let records = fields.iter().map(|ExposedField { key, binding, .. }| {
+        quote! { #binding.record(#key, __visitor); }
+    });
+
+    Ok(quote! {
+        #pattern => {
+            #values
+            #(#records)*
+        }
+    })
+}
+
+/// Generates an `HttpError` impl for the type, if any of its shapes has HTTP arguments.
+pub fn generate_http_error_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    shapes: &[Shape],
+) -> syn::Result<Option<TokenStream>> {
+    if shapes
+        .iter()
+        .all(|s| s.attrs.status.is_none() && s.attrs.type_uri.is_none() && s.attrs.title.is_none())
+    {
+        return Ok(None);
+    }
+
+    let status_arms = shapes.iter().map(|shape| {
+        let path = &shape.path;
+        match &shape.attrs.status {
+            _ if delegates(shape) => {
+                let source = source_as_trait(shape, "HttpRef", "http_error");
+                quote! { #path { source, .. } => #source.map_or(500, ::easyerr::HttpError::status) }
+            }
+            Some(status) => quote! { #path { .. } => #status },
+            None => quote! { #path { .. } => 500 },
+        }
+    });
+
+    let optional_arms = |get: fn(&Shape) -> Option<TokenStream>, method: TokenStream| {
+        shapes
+            .iter()
+            .map(|shape| {
+                let path = &shape.path;
+                match get(shape) {
+                    _ if delegates(shape) => {
+                        let source = source_as_trait(shape, "HttpRef", "http_error");
+                        quote! {
+                            #path { source, .. } => #source.and_then(::easyerr::HttpError::#method)
+                        }
+                    }
+                    Some(value) => quote! { #path { .. } => Some(#value) },
+                    None => quote! { #path { .. } => None },
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let type_uri_arms = optional_arms(
+        |s| s.attrs.type_uri.as_ref().map(|t| quote! { #t }),
+        quote! { type_uri },
+    );
+    let title_arms = optional_arms(
+        |s| s.attrs.title.as_ref().map(|t| quote! { #t }),
+        quote! { title },
+    );
+    let extensions_arms = shapes
+        .iter()
+        .map(generate_extensions_arm)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let traits = field_traits();
+    let http_traits = quote! {
+        #[allow(unused_imports)]
+        use ::easyerr::__private::{NoHttp as _, ViaHttp as _};
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(Some(quote! {
+        impl #impl_generics ::easyerr::HttpError for #ty_ident #ty_generics #where_clause {
+            fn status(&self) -> u16 {
+                #http_traits
+
+                match self {
+                    #(#status_arms),*
+                }
+            }
+
+            fn type_uri(&self) -> Option<&'static str> {
+                #http_traits
+
+                match self {
+                    #(#type_uri_arms),*
+                }
+            }
+
+            fn title(&self) -> Option<&'static str> {
+                #http_traits
+
+                match self {
+                    #(#title_arms),*
+                }
+            }
+
+            #[allow(unused_variables)]
+            fn visit_extensions(&self, __visitor: &mut dyn ::easyerr::FieldVisitor) {
+                #traits
+                #http_traits
+
+                match self {
+                    #(#extensions_arms)*
+                }
+            }
+        }
+    }))
+}
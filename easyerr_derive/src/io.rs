@@ -0,0 +1,100 @@
+use crate::{attr::ErrorAttrs, is_optional_source, source_error_type, source_field_of, Shape};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Generics, Ident};
+
+/// Generates a `From<Type> for std::io::Error` impl, if the type has `into_io` or any of its shapes
+/// has an `io_kind`.
+///
+/// Shapes without an `io_kind` use `ErrorKind::Other`, except for transparent ones, which convert
+/// their source into an `io::Error` instead if it implements `Into<io::Error>`. Optional sources are
+/// converted when present, and use `ErrorKind::Other` when missing.
+pub fn generate_io_error_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    attrs: &ErrorAttrs,
+    shapes: &[Shape],
+    is_enum: bool,
+) -> syn::Result<TokenStream> {
+    if !attrs.into_io && shapes.iter().all(|s| s.attrs.io_kind.is_none()) {
+        return Ok(TokenStream::new());
+    }
+
+    if !cfg!(feature = "std") {
+        return Err(Error::new(
+            ty_ident.span(),
+            "`into_io` and `io_kind` require the `std` feature of easyerr",
+        ));
+    }
+
+    let arms = shapes.iter().map(|shape| {
+        // patterns can't use `Self` here, since it refers to `io::Error`
+        let ident = shape.ident;
+        let path = if is_enum {
+            quote! { #ty_ident::#ident }
+        } else {
+            quote! { #ty_ident }
+        };
+
+        match &shape.attrs.io_kind {
+            Some(kind) => quote! {
+                err @ #path { .. } => ::std::io::Error::new(::std::io::ErrorKind::#kind, err)
+            },
+            None if shape.attrs.is_transparent() => match source_field_of(shape.fields.iter()) {
+                Some(field) if is_optional_source(field) => {
+                    let ty = source_error_type(field);
+                    quote! {
+                        err @ #path { source: ::core::option::Option::Some(_), .. } => {
+                            (&&::easyerr::__private::IoSource::<#ty>::new()).convert(err, |err| match err {
+                                #path { source: ::core::option::Option::Some(source), .. } => source,
+                                #[allow(unreachable_patterns)]
+                                _ => ::core::unreachable!(),
+                            })
+                        },
+                        err @ #path { .. } => ::std::io::Error::other(err)
+                    }
+                }
+                Some(field) => {
+                    let ty = &field.ty;
+                    quote! {
+                        err @ #path { .. } => (&&::easyerr::__private::IoSource::<#ty>::new())
+                            .convert(err, |err| match err {
+                                #path { source, .. } => source,
+                                #[allow(unreachable_patterns)]
+                                _ => ::core::unreachable!(),
+                            })
+                    }
+                }
+                None => quote! {
+                    err @ #path { .. } => ::std::io::Error::other(err)
+                },
+            },
+            None => quote! {
+                err @ #path { .. } => ::std::io::Error::new(::std::io::ErrorKind::Other, err)
+            },
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut where_clause = where_clause
+        .cloned()
+        .unwrap_or_else(|| syn::parse_quote!(where));
+    where_clause.predicates.push(syn::parse_quote! {
+        #ty_ident #ty_generics: ::core::marker::Send + ::core::marker::Sync + 'static
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::From<#ty_ident #ty_generics> for ::std::io::Error
+        #where_clause
+        {
+            fn from(err: #ty_ident #ty_generics) -> Self {
+                #[allow(unused_imports)]
+                use ::easyerr::__private::{OtherIo as _, ViaIntoIo as _};
+
+                match err {
+                    #(#arms),*
+                }
+            }
+        }
+    })
+}
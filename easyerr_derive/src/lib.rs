@@ -1,43 +1,571 @@
+mod attr;
+mod boxed;
+mod catalog;
+mod code;
+mod debug;
+mod defmt;
 mod enum_;
+mod exit;
+mod fields;
+mod hints;
+mod http;
+mod io;
+mod log;
+mod miette;
+mod raw;
+mod ser;
 mod struct_;
 
+use attr::{ErrorAttrs, Location, Message, RedactMode, SourceMode};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
 use syn::{
-    parse::Parse,
-    parse_macro_input,
+    ext::IdentExt,
+    parse_macro_input, parse_quote,
     spanned::Spanned,
     visit::{self, Visit},
-    Error, Field, GenericParam, Generics, Ident, Item, Lifetime, LitStr, Type, TypePath,
+    Attribute, Expr, ExprLit, Field, Fields, GenericArgument, GenericParam, Generics, Ident, Item,
+    Lifetime, Lit, LitStr, Meta, MetaNameValue, PathArguments, Type, TypeParamBound, TypePath,
 };
 
-#[derive(PartialEq, Eq)]
-enum ErrorAttrArg {
-    Str(LitStr),
-    Transparent,
+/// Either a struct or one of the variants of an enum.
+struct Shape<'a> {
+    /// Path used to construct or match on this shape: `Self` or `Self::Variant`.
+    path: TokenStream,
+    ident: &'a Ident,
+    fields: &'a Fields,
+    attrs: ErrorAttrs,
+    /// Doc comments of this shape.
+    docs: String,
 }
 
-impl Parse for ErrorAttrArg {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let ident: Result<Ident, _> = input.parse();
-        if let Ok(ident) = ident {
-            if ident == "transparent" {
-                return Ok(Self::Transparent);
+impl<'a> Shape<'a> {
+    fn new(
+        path: TokenStream,
+        ident: &'a Ident,
+        fields: &'a Fields,
+        attrs: &[Attribute],
+        location: Location,
+    ) -> syn::Result<Self> {
+        let shape = Self {
+            path,
+            ident,
+            fields,
+            attrs: ErrorAttrs::parse(attrs, location)?,
+            docs: doc_string(attrs),
+        };
+
+        // a transparent shape displays its source, so it needs a message for when it has none
+        let optional = source_field_of(fields.iter()).is_some_and(is_optional_source);
+        match (&shape.attrs.message, &shape.attrs.fallback) {
+            (Some(Message::Transparent(span)), None) if optional => {
+                return Err(syn::Error::new(
+                    *span,
+                    "missing `fallback` argument. a `transparent` struct or variant with an \
+                     optional source needs a message for when it has no source.",
+                ));
+            }
+            (Some(Message::Transparent(_)), Some(_)) if optional => {}
+            (_, Some(fallback)) => {
+                return Err(syn::Error::new(
+                    fallback.span(),
+                    "`fallback` is only allowed on `transparent` structs and variants with an \
+                     optional source",
+                ));
             }
+            _ => {}
+        }
+
+        Ok(shape)
+    }
+
+    /// Identifiers the fields of this shape are bound to by [`Shape::pattern`]. Tuple fields are
+    /// bound to `f{index}`.
+    fn bindings(&self) -> Vec<Ident> {
+        field_bindings(self.fields)
+    }
+
+    /// Pattern which binds every field of this shape.
+    fn pattern(&self) -> TokenStream {
+        let path = &self.path;
+        let bindings = self.bindings();
+        match self.fields {
+            Fields::Named(_) => quote! { #path { #(#bindings),* } },
+            Fields::Unnamed(_) => quote! { #path(#(#bindings),*) },
+            Fields::Unit => quote! { #path },
+        }
+    }
+}
+
+/// Joins the lines of the doc comments in `attrs`.
+fn doc_string(attrs: &[Attribute]) -> String {
+    let lines: Vec<_> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            Meta::NameValue(MetaNameValue {
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(s), ..
+                    }),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        })
+        .collect();
+
+    lines
+        .iter()
+        .map(|l| l.strip_prefix(' ').unwrap_or(l))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_owned()
+}
+
+fn field_bindings(fields: &Fields) -> Vec<Ident> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| f.ident.clone().unwrap_or_else(|| format_ident!("f{i}")))
+        .collect()
+}
+
+/// Shadows the bindings of redacted fields with a [`Redacted`] wrapper which decides whether to
+/// reveal them based on the formatter `f`.
+///
+/// [`Redacted`]: https://docs.rs/easyerr/latest/easyerr/struct.Redacted.html
+fn redact_bindings(fields: &Fields) -> syn::Result<TokenStream> {
+    let mut result = TokenStream::new();
+    for (field, binding) in fields.iter().zip(field_bindings(fields)) {
+        let attrs = ErrorAttrs::parse(&field.attrs, Location::Field)?;
+        let Some(mode) = attrs.redact else {
+            continue;
+        };
 
-            return Err(Error::new(
-                input.span(),
-                "unknown error argument. valid arguments are `transparent` or a format string.",
+        result.extend(quote! {
+            let #binding = ::easyerr::Redacted::new(#binding, #mode, f);
+        });
+    }
+
+    Ok(result)
+}
+
+/// Writes the message of `shape`, which has the format message `message`, after `prefix`.
+///
+/// When built with `--cfg easyerr_minimal`, the message is replaced by the raw code of the shape
+/// (its `errno` or its `discriminant`) if it has one, or by its `name` otherwise, so that message
+/// templates don't end up in the binary.
+fn write_message(
+    shape: &Shape,
+    name: &str,
+    message: &LitStr,
+    prefix: &TokenStream,
+    discriminant: Option<&(TokenStream, Option<i64>)>,
+) -> syn::Result<TokenStream> {
+    let minimal = match (&shape.attrs.errno, discriminant) {
+        (Some(errno), _) => {
+            let errno = errno.base10_digits();
+            quote! { f.write_str(#errno)?; }
+        }
+        (None, Some((_, Some(value)))) => {
+            let value = value.to_string();
+            quote! { f.write_str(#value)?; }
+        }
+        (None, Some((discriminant, None))) => quote! { write!(f, "{}", #discriminant)?; },
+        (None, None) => quote! { f.write_str(#name)?; },
+    };
+
+    let full = if let Some(literal) = literal_message(message) {
+        quote! { f.write_str(#literal)?; }
+    } else {
+        let message = resolve_positional(message, shape.fields)?;
+        let redactions = redact_bindings(shape.fields)?;
+        quote! {
+            #redactions
+            write!(f, #message)?;
+        }
+    };
+
+    Ok(quote! {
+        #prefix
+        #[cfg(easyerr_minimal)]
+        {
+            #minimal
+        }
+        #[cfg(not(easyerr_minimal))]
+        {
+            #full
+        }
+    })
+}
+
+/// Replaces the positional placeholders of the format message `message` (`{}` and `{0}`, with or
+/// without a format spec) by the bindings of the matching `fields`, so that `{}` refers to the first
+/// field, the next `{}` to the second one and so on.
+fn resolve_positional(message: &LitStr, fields: &Fields) -> syn::Result<LitStr> {
+    let bindings = field_bindings(fields);
+    let value = message.value();
+    let mut resolved = String::with_capacity(value.len());
+    let mut next = 0;
+
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        resolved.push(c);
+        if c != '{' {
+            continue;
+        }
+
+        if chars.peek() == Some(&'{') {
+            resolved.extend(chars.next());
+            continue;
+        }
+
+        let mut arg = String::new();
+        while let Some(&c) = chars.peek().filter(|&&c| c != ':' && c != '}') {
+            arg.push(c);
+            chars.next();
+        }
+
+        let index = if arg.is_empty() {
+            next += 1;
+            next - 1
+        } else if let Ok(index) = arg.parse::<usize>() {
+            index
+        } else {
+            resolved.push_str(&arg);
+            continue;
+        };
+
+        let Some(binding) = bindings.get(index) else {
+            return Err(syn::Error::new(
+                message.span(),
+                format!("positional placeholder `{{{index}}}` has no matching field"),
             ));
+        };
+
+        resolved.push_str(&binding.to_string());
+    }
+
+    Ok(LitStr::new(&resolved, message.span()))
+}
+
+/// Unescapes the format message `message` if it has no placeholders, so it can be written with
+/// `write_str` instead of going through the formatting machinery.
+fn literal_message(message: &LitStr) -> Option<String> {
+    let message = message.value();
+    let mut literal = String::with_capacity(message.len());
+    let mut chars = message.chars();
+    while let Some(c) = chars.next() {
+        if matches!(c, '{' | '}') && chars.next() != Some(c) {
+            return None;
+        }
+        literal.push(c);
+    }
+
+    Some(literal)
+}
+
+/// Expression evaluating to `full`, or to `minimal` when built with `--cfg easyerr_minimal`, which
+/// leaves `full` out of the binary. Used for the templates other than messages, such as hints or
+/// catalog entries. Needs `unexpected_cfgs` to be allowed where it expands.
+fn unless_minimal(minimal: &TokenStream, full: &TokenStream) -> TokenStream {
+    quote! {{
+        #[cfg(easyerr_minimal)]
+        let value = #minimal;
+        #[cfg(not(easyerr_minimal))]
+        let value = #full;
+        value
+    }}
+}
+
+/// Writes the message of `source`, a reference to the source field of the `transparent` `shape`.
+/// Optional sources, which always come with a `fallback` message, write it when they're `None`, or
+/// the `name` of the shape when built with `--cfg easyerr_minimal`.
+fn write_transparent(shape: &Shape, name: &str, source: &TokenStream) -> TokenStream {
+    let delegate = quote! {
+        if f.alternate() {
+            ::easyerr::__private::fmt_message(f, source)?;
+        } else {
+            ::core::fmt::Display::fmt(source, f)?;
+        }
+    };
+
+    if let Some(fallback) = &shape.attrs.fallback {
+        quote! {
+            if let Some(source) = #source {
+                #delegate
+            } else {
+                #[cfg(easyerr_minimal)]
+                f.write_str(#name)?;
+                #[cfg(not(easyerr_minimal))]
+                f.write_str(#fallback)?;
+            }
+        }
+    } else {
+        quote! {
+            let source = #source;
+            #delegate
+        }
+    }
+}
+
+/// A field which is exposed outside of the message of its error, e.g. when serializing it.
+struct ExposedField {
+    /// Name of the field, or its index for tuple fields.
+    key: String,
+    binding: Ident,
+    redact: Option<RedactMode>,
+    /// Whether the field is serialized, i.e. isn't marked with `#[error(serialize = false)]`.
+    serialize: bool,
+}
+
+/// Fields which are exposed outside of the message of their error: every field except for the
+/// source and those marked with `#[error(skip)]`.
+fn exposed_fields(fields: &Fields) -> syn::Result<Vec<ExposedField>> {
+    let mut result = Vec::new();
+    for (i, (field, binding)) in fields.iter().zip(field_bindings(fields)).enumerate() {
+        if field.ident.as_ref().is_some_and(|i| i == "source") {
+            continue;
+        }
+
+        let attrs = ErrorAttrs::parse(&field.attrs, Location::Field)?;
+        if attrs.source.is_some() {
+            return Err(syn::Error::new(
+                field.span(),
+                "`source` is only allowed on the source field",
+            ));
+        }
+
+        if attrs.skip {
+            continue;
+        }
+
+        let key = match &field.ident {
+            Some(ident) => ident.unraw().to_string(),
+            None => i.to_string(),
+        };
+
+        result.push(ExposedField {
+            key,
+            binding,
+            redact: attrs.redact,
+            serialize: attrs.serialize != Some(false),
+        });
+    }
+
+    Ok(result)
+}
+
+/// Writes the `[code] ` prefix of the message of `shape`, if it has a code and `show_code` is set.
+fn code_prefix(shape: &Shape, show_code: bool) -> TokenStream {
+    match &shape.attrs.code {
+        Some(code) if show_code => {
+            let prefix = format!("[{}] ", code.value());
+            quote! { f.write_str(#prefix)?; }
         }
+        _ => TokenStream::new(),
+    }
+}
+
+/// How the source field of a shape is stored.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SourceKind {
+    /// The source implements `Error` and is stored as is.
+    Plain,
+    /// The source is an error report type which derefs to `dyn Error` instead of implementing it:
+    /// `anyhow::Error` with the `anyhow` feature, `eyre::Report` with the `eyre` feature, or any
+    /// type marked with `#[error(source(report))]`.
+    Report,
+    /// The source is a boxed `dyn Error`, either detected from its type or marked with
+    /// `#[error(source(boxed))]`.
+    Boxed,
+}
+
+fn source_kind(field: &Field) -> syn::Result<SourceKind> {
+    let attrs = ErrorAttrs::parse(&field.attrs, Location::Field)?;
+    let ty = source_error_type(field);
+    Ok(
+        if matches!(attrs.source, Some(SourceMode::Boxed)) || is_boxed_error_type(ty) {
+            SourceKind::Boxed
+        } else if matches!(attrs.source, Some(SourceMode::Report)) || is_report_type(ty) {
+            SourceKind::Report
+        } else {
+            SourceKind::Plain
+        },
+    )
+}
+
+/// The argument of `ty` if it is an `Option<T>`.
+fn option_argument(ty: &Type) -> Option<&Type> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+
+    let last = path.segments.last().filter(|s| s.ident == "Option")?;
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+
+    match args.args.first() {
+        Some(GenericArgument::Type(ty)) if args.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
+
+/// Whether the source field `field` is optional, i.e. an `Option<E>`.
+fn is_optional_source(field: &Field) -> bool {
+    option_argument(&field.ty).is_some()
+}
+
+/// Type of the error stored in the source field `field`: `E` for an `Option<E>`.
+fn source_error_type(field: &Field) -> &Type {
+    option_argument(&field.ty).unwrap_or(&field.ty)
+}
+
+/// Whether `ty` is a `Box<dyn Error ...>`, where `Error` is spelled `Error`, `StdError`,
+/// `std::error::Error` or `core::error::Error`. Other aliases need `#[error(source(boxed))]`.
+fn is_boxed_error_type(ty: &Type) -> bool {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return false;
+    };
+
+    let Some(last) = path.segments.last().filter(|s| s.ident == "Box") else {
+        return false;
+    };
 
-        let format_str: Result<LitStr, _> = input.parse();
-        if let Ok(format_str) = format_str {
-            return Ok(Self::Str(format_str));
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return false;
+    };
+
+    let Some(GenericArgument::Type(Type::TraitObject(object))) = args.args.first() else {
+        return false;
+    };
+
+    object.bounds.iter().any(|bound| match bound {
+        TypeParamBound::Trait(t) => {
+            let path = t.path.segments.iter().map(|s| s.ident.to_string());
+            matches!(
+                path.collect::<Vec<_>>().join("::").as_str(),
+                "Error" | "StdError" | "std::error::Error" | "core::error::Error"
+            )
         }
+        _ => false,
+    })
+}
 
-        Err(Error::new(
-            input.span(),
-            "unknown error argument. valid arguments are `transparent` or a format string.",
-        ))
+/// Whether `ty` is an error report type (see [`SourceKind::Report`]). This only looks at the last
+/// two segments of the path, so aliases and re-exports need `#[error(source(report))]`.
+fn is_report_type(ty: &Type) -> bool {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return false;
+    };
+
+    let mut segments = path.segments.iter().rev().map(|s| s.ident.to_string());
+    let (Some(last), Some(krate)) = (segments.next(), segments.next()) else {
+        return false;
+    };
+
+    (cfg!(feature = "anyhow") && krate == "anyhow" && last == "Error")
+        || (cfg!(feature = "eyre") && krate == "eyre" && last == "Report")
+}
+
+/// Converts `source`, a reference to the source field `field`, into an `Option<&dyn Error>`,
+/// which is `None` only for optional sources.
+fn source_as_dyn(field: &Field, source: TokenStream) -> syn::Result<TokenStream> {
+    let kind = source_kind(field)?;
+    let as_dyn = |source: TokenStream| match kind {
+        SourceKind::Plain => source,
+        SourceKind::Report | SourceKind::Boxed => quote! { &**#source },
+    };
+
+    Ok(if is_optional_source(field) {
+        let inner = as_dyn(quote! { source });
+        quote! {
+            ::core::option::Option::as_ref(#source).map(|source| {
+                let source: &(dyn ::core::error::Error + 'static) = #inner;
+                source
+            })
+        }
+    } else {
+        let source = as_dyn(source);
+        quote! { ::core::option::Option::Some::<&(dyn ::core::error::Error + 'static)>(#source) }
+    })
+}
+
+/// The source accepted by the `ErrorContext` impl of a context whose source field is `field`.
+struct ContextSource {
+    /// Generics of the impl: those of the error type, plus the type of the source for boxed
+    /// sources.
+    generics: Generics,
+    /// Type of the accepted source.
+    ty: TokenStream,
+    /// Expression which turns the accepted `source` into the value of the field.
+    store: TokenStream,
+}
+
+fn context_source(field: &Field, generics: &Generics) -> syn::Result<ContextSource> {
+    let attrs = ErrorAttrs::parse(&field.attrs, Location::Field)?;
+    let error_ty = source_error_type(field);
+    let mut generics = generics.clone();
+    let (ty, store) = if let Some(SourceMode::From(ty, convert)) = attrs.source {
+        (quote! { #ty }, quote! { (#convert)(source) })
+    } else if source_kind(field)? == SourceKind::Boxed {
+        generics.params.push(parse_quote! { __S });
+        // not bounded by `Error`, so that already boxed sources are accepted as well
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote! { #error_ty: ::core::convert::From<__S> });
+
+        (
+            quote! { __S },
+            quote! { ::core::convert::From::from(source) },
+        )
+    } else {
+        (quote! { #error_ty }, quote! { source })
+    };
+
+    let store = if is_optional_source(field) {
+        quote! { ::core::option::Option::Some(#store) }
+    } else {
+        store
+    };
+
+    Ok(ContextSource {
+        generics,
+        ty,
+        store,
+    })
+}
+
+/// Generates the leaf selector of a shape with an optional source: `def` defines the selector,
+/// which has the same fields as the context, and is used as `leaf` (with its generics). Its `build`
+/// and `fail` methods create the error `ty` without a source through the expression `error`.
+/// `generics` are those of the error type.
+fn generate_leaf_selector(
+    def: &TokenStream,
+    leaf: &TokenStream,
+    generics: &Generics,
+    ty: &TokenStream,
+    error: &TokenStream,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    quote! {
+        #def
+
+        impl #impl_generics #leaf #where_clause {
+            /// Creates the error, without a source.
+            #[cold]
+            pub fn build(self) -> #ty {
+                #error
+            }
+
+            /// Creates the error, without a source, wrapped in an `Err`.
+            pub fn fail<__T>(self) -> ::core::result::Result<__T, #ty> {
+                ::core::result::Result::Err(self.build())
+            }
+        }
     }
 }
 
@@ -45,13 +573,59 @@ fn source_field_of<'f>(mut fields: impl Iterator<Item = &'f Field>) -> Option<&'
     fields.find(|f| f.ident.as_ref().is_some_and(|i| i == "source"))
 }
 
-fn extract_source_field<'f>(fields: impl Iterator<Item = &'f Field>) -> (Vec<Field>, Option<Type>) {
-    let mut source_ty = None;
-    let fields = fields
+// Impls which forward to the source of transparent shapes reach it through one of the two helpers
+// below: `source_as_trait` for traits with a fallback (exit codes, HTTP errors, diagnostics...) and
+// `source_method` for traits without one (error codes and raw codes). The `io::Error` conversion
+// is the exception, since it takes the source by value: see `io.rs`.
+
+/// Expression evaluating to `(&&#wrapper(source)).#method()` for the `source` binding of `shape`.
+/// `wrapper` is one of the autoref wrappers of `easyerr::__private`, whose `method` returns the
+/// source as an `Option<&dyn Trait>`, which is `None` if the source doesn't implement `Trait`. For
+/// optional sources, the expression is `None` as well if the source is missing. Boxed and report
+/// sources don't implement any trait of the crate, so they are always `None`.
+fn source_as_trait(shape: &Shape, wrapper: &str, method: &str) -> TokenStream {
+    let wrapper = format_ident!("{}", wrapper);
+    let method = format_ident!("{}", method);
+    let value = quote! { (&&::easyerr::__private::#wrapper(source)).#method() };
+    match source_field_of(shape.fields.iter()) {
+        Some(field) if is_optional_source(field) => quote! {
+            ::core::option::Option::as_ref(source).and_then(|source| #value)
+        },
+        _ => value,
+    }
+}
+
+/// Expression evaluating to `#method(source)` for the `source` binding of the transparent `shape`,
+/// where `method` is a path to a trait method without a fallback. Optional, boxed and report
+/// sources can't always provide a value, so such shapes need their own `argument` instead.
+fn source_method(shape: &Shape, method: &TokenStream, argument: &str) -> syn::Result<TokenStream> {
+    let Some(field) = source_field_of(shape.fields.iter()) else {
+        return Ok(quote! { #method(source) });
+    };
+
+    if is_optional_source(field) || source_kind(field)? != SourceKind::Plain {
+        return Err(syn::Error::new(
+            field.ty.span(),
+            format!(
+                "an optional, boxed or report source can't provide the `{argument}` of a \
+                 transparent error. add a `{argument}` argument to this struct or variant."
+            ),
+        ));
+    }
+
+    // spanned so that a source which doesn't implement the trait is reported on the source field
+    Ok(quote_spanned! { field.ty.span() => #method(source) })
+}
+
+fn extract_source_field<'f>(
+    fields: impl Iterator<Item = &'f Field>,
+) -> (Vec<Field>, Option<Field>) {
+    let mut source = None;
+    let mut fields: Vec<Field> = fields
         .filter(|&f| {
             f.ident.as_ref().is_some_and(|i| {
                 if i == "source" {
-                    source_ty = Some(f.ty.clone());
+                    source = Some(f.clone());
                     false
                 } else {
                     true
@@ -61,7 +635,12 @@ fn extract_source_field<'f>(fields: impl Iterator<Item = &'f Field>) -> (Vec<Fie
         .cloned()
         .collect();
 
-    (fields, source_ty)
+    for f in &mut fields {
+        f.attrs
+            .retain(|a| !a.path().is_ident("error") && !a.path().is_ident("label"));
+    }
+
+    (fields, source)
 }
 
 fn is_required_generic_for_type(ty: &Type, is_const: bool, generic: &Ident) -> bool {
@@ -152,7 +731,7 @@ fn generics_required_by_type(generics: &Generics, ty: &Type) -> Vec<GenericParam
     result
 }
 
-#[proc_macro_derive(Error, attributes(error))]
+#[proc_macro_derive(Error, attributes(error, label))]
 pub fn derive_error(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input: Item = parse_macro_input!(input);
 
@@ -167,3 +746,32 @@ pub fn derive_error(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         Err(e) => e.into_compile_error().into(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::literal_message;
+    use syn::LitStr;
+
+    fn literal(message: &str) -> Option<String> {
+        literal_message(&LitStr::new(message, proc_macro2::Span::call_site()))
+    }
+
+    #[test]
+    fn literal_messages() {
+        assert_eq!(literal("plain").as_deref(), Some("plain"));
+        assert_eq!(literal("{{}}").as_deref(), Some("{}"));
+        assert_eq!(literal("}}{{").as_deref(), Some("}{"));
+        assert_eq!(literal("").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn formatted_messages() {
+        assert_eq!(literal("{x}"), None);
+        assert_eq!(literal("{}"), None);
+        assert_eq!(literal("{{{x}}}"), None);
+        // lone braces are left to `write!`, which reports them
+        assert_eq!(literal("trailing }"), None);
+        assert_eq!(literal("trailing {"), None);
+        assert_eq!(literal("}x"), None);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    exposed_fields,
+    fields::{bind_field_values, field_traits},
+    source_as_trait, Shape,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Generics, Ident};
+
+fn generate_log_arm(shape: &Shape) -> syn::Result<TokenStream> {
+    let pattern = shape.pattern();
+    let fields = exposed_fields(shape.fields)?;
+
+    let values = bind_field_values(&fields);
+    let keys = fields.iter().map(|f| &f.key).collect::<Vec<_>>();
+    let bindings = fields.iter().map(|f| &f.binding).collect::<Vec<_>>();
+    let log = cfg!(feature = "log").then(|| {
+        quote! {
+            ::easyerr::__private::log_error(
+                &self,
+                __level,
+                ::core::module_path!(),
+                &[#((#keys, &#bindings as &dyn ::core::fmt::Display)),*],
+            );
+        }
+    });
+    let tracing = cfg!(feature = "tracing").then(|| {
+        quote! {
+            ::easyerr::__private::trace_error!(__level, self, #(#keys = #bindings),*);
+        }
+    });
+
+    if !shape.attrs.is_transparent() {
+        return Ok(quote! {
+            #pattern => {
+                #values
+                #log
+                #tracing
+            }
+        });
+    }
+
+    // transparent shapes are logged as their source, if it implements `LogError`
+    let source = source_as_trait(shape, "LogRef", "log_error");
+    Ok(quote! {
+        #pattern => match #source {
+            ::core::option::Option::Some(source) => source.log(__level),
+            ::core::option::Option::None => {
+                #values
+                #log
+                #tracing
+            }
+        }
+    })
+}
+
+/// Generates a `LogError` impl for the type. Its `log` method is only generated if the `log` or
+/// `tracing` features are enabled.
+///
+/// Transparent shapes without a level use the level of their source, if it implements `LogError`,
+/// and are logged as their source.
+pub fn generate_log_error_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    shapes: &[Shape],
+) -> syn::Result<TokenStream> {
+    let level = shapes
+        .iter()
+        .any(|s| s.attrs.level.is_some() || s.attrs.is_transparent())
+        .then(|| {
+            let arms = shapes.iter().map(|shape| {
+                let path = &shape.path;
+                match &shape.attrs.level {
+                    Some(level) => quote! { #path { .. } => ::easyerr::Level::#level },
+                    None if shape.attrs.is_transparent() => {
+                        let source = source_as_trait(shape, "LogRef", "log_error");
+                        quote! {
+                            #path { source, .. } => {
+                                #source.map_or(::easyerr::Level::Error, ::easyerr::LogError::level)
+                            }
+                        }
+                    }
+                    None => quote! { #path { .. } => ::easyerr::Level::Error },
+                }
+            });
+
+            quote! {
+                fn level(&self) -> ::easyerr::Level {
+                    #[allow(unused_imports)]
+                    use ::easyerr::__private::{NoLog as _, ViaLog as _};
+
+                    match self {
+                        #(#arms),*
+                    }
+                }
+            }
+        });
+
+    let log = if cfg!(any(feature = "log", feature = "tracing")) {
+        let arms = shapes
+            .iter()
+            .map(generate_log_arm)
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let traits = field_traits();
+        Some(quote! {
+            #[allow(unused_variables)]
+            fn log(&self, __level: ::easyerr::Level) {
+                #traits
+                #[allow(unused_imports)]
+                use ::easyerr::__private::{NoLog as _, ViaLog as _};
+
+                match self {
+                    #(#arms)*
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::easyerr::LogError for #ty_ident #ty_generics #where_clause {
+            #level
+            #log
+        }
+    })
+}
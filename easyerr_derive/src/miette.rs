@@ -0,0 +1,311 @@
+use crate::{
+    attr::{ErrorAttrs, LabelText, Location},
+    field_bindings, redact_bindings, resolve_positional, source_as_trait, source_field_of,
+    unless_minimal, Shape,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Fields, Generics, Ident, LitStr};
+
+/// A field with a `#[label]` or `#[label("...")]` attribute (or their `#[error(label)]` forms).
+struct Label {
+    binding: Ident,
+    text: Option<LitStr>,
+}
+
+/// The fields of a shape which are relevant to `miette`.
+struct DiagnosticFields {
+    labels: Vec<Label>,
+    source_code: Option<Ident>,
+    /// Bindings of redacted fields, with their redaction modes.
+    redactions: TokenStream,
+}
+
+impl DiagnosticFields {
+    fn new(fields: &Fields) -> syn::Result<Self> {
+        let mut labels = Vec::new();
+        let mut source_code = None;
+        let mut redactions = TokenStream::new();
+        for (field, binding) in fields.iter().zip(field_bindings(fields)) {
+            let attrs = ErrorAttrs::parse(&field.attrs, Location::Field)?;
+            if let Some(text) = attrs.label {
+                labels.push(Label {
+                    binding: binding.clone(),
+                    text: match text {
+                        LabelText::None => None,
+                        LabelText::Text(text) => Some(resolve_positional(&text, fields)?),
+                    },
+                });
+            }
+
+            if attrs.source_code {
+                if source_code.is_some() {
+                    return Err(Error::new(binding.span(), "duplicate `source_code` field"));
+                }
+
+                source_code = Some(binding.clone());
+            }
+
+            if let Some(mode) = attrs.redact {
+                redactions.extend(quote! {
+                    let #binding = ::easyerr::Redacted::scoped(#binding, #mode);
+                });
+            }
+        }
+
+        Ok(Self {
+            labels,
+            source_code,
+            redactions,
+        })
+    }
+}
+
+/// Whether any of the given shapes uses attributes which only make sense with `miette`: `url`,
+/// `severity`, `label` or `source_code`.
+fn uses_miette(shapes: &[Shape]) -> syn::Result<bool> {
+    for shape in shapes {
+        if shape.attrs.url.is_some() || shape.attrs.severity.is_some() {
+            return Ok(true);
+        }
+
+        for field in shape.fields {
+            let attrs = ErrorAttrs::parse(&field.attrs, Location::Field)?;
+            if attrs.label.is_some() || attrs.source_code {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Generates the match arms of a `Diagnostic` method. Shapes for which `own` returns `None` use
+/// `default` if they're not transparent, and forward the method to their source otherwise.
+fn method_arms(
+    shapes: &[Shape],
+    method: &TokenStream,
+    default: &TokenStream,
+    mut own: impl FnMut(&Shape) -> syn::Result<Option<TokenStream>>,
+) -> syn::Result<Vec<TokenStream>> {
+    let mut arms = Vec::new();
+    for shape in shapes {
+        let path = &shape.path;
+        arms.push(match own(shape)? {
+            Some(body) => {
+                let pattern = shape.pattern();
+                quote! { #pattern => { #body } }
+            }
+            None if shape.attrs.is_transparent() => {
+                let source = source_as_trait(shape, "DiagnosticRef", "diagnostic");
+                quote! {
+                    #path { source, .. } => {
+                        #source.and_then(::easyerr::__private::miette::Diagnostic::#method)
+                    }
+                }
+            }
+            None => quote! { #path { .. } => #default },
+        });
+    }
+
+    Ok(arms)
+}
+
+/// Generates a `miette::Diagnostic` impl for the type, if it has `#[error(diagnostic)]` or any of
+/// its shapes uses `miette` attributes. `help` alone doesn't, since it is used for `Hints` too.
+pub fn generate_diagnostic_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    attrs: &ErrorAttrs,
+    shapes: &[Shape],
+) -> syn::Result<TokenStream> {
+    if !attrs.diagnostic && !uses_miette(shapes)? {
+        return Ok(TokenStream::new());
+    }
+
+    if !cfg!(feature = "miette") {
+        return Err(Error::new(
+            ty_ident.span(),
+            "`diagnostic`, `url`, `severity`, `source_code` and `label` require the `miette` \
+             feature of easyerr",
+        ));
+    }
+
+    let none = quote! { None };
+    // formatted like messages, with the formatter deciding whether to reveal redacted fields
+    let boxed_format = |shape: &Shape, format: &LitStr| -> syn::Result<TokenStream> {
+        let format = resolve_positional(format, shape.fields)?;
+        let redactions = redact_bindings(shape.fields)?;
+        let display = quote! {
+            ::easyerr::__private::Box::new(::easyerr::__private::DisplayFn(
+                move |f: &mut ::core::fmt::Formatter<'_>| {
+                    #redactions
+                    write!(f, #format)
+                },
+            )) as ::easyerr::__private::Box<dyn ::core::fmt::Display + '_>
+        };
+        Ok(unless_minimal(&none, &quote! { Some(#display) }))
+    };
+
+    let code_arms = method_arms(shapes, &quote! { code }, &none, |shape| {
+        Ok(shape
+            .attrs
+            .code
+            .as_ref()
+            .map(|code| quote! { Some(::easyerr::__private::Box::new(#code)) }))
+    })?;
+    let severity_arms = method_arms(shapes, &quote! { severity }, &none, |shape| {
+        Ok(shape.attrs.severity.as_ref().map(|severity| {
+            quote! { Some(::easyerr::__private::miette::Severity::#severity) }
+        }))
+    })?;
+    let help_arms = method_arms(shapes, &quote! { help }, &none, |shape| {
+        shape
+            .attrs
+            .help
+            .as_ref()
+            .map(|help| boxed_format(shape, help))
+            .transpose()
+    })?;
+    let url_arms = method_arms(shapes, &quote! { url }, &none, |shape| {
+        shape
+            .attrs
+            .url
+            .as_ref()
+            .map(|url| boxed_format(shape, url))
+            .transpose()
+    })?;
+    let source_code_arms = method_arms(shapes, &quote! { source_code }, &none, |shape| {
+        Ok(DiagnosticFields::new(shape.fields)?
+            .source_code
+            .map(|binding| quote! { Some(#binding) }))
+    })?;
+    let labels_arms = method_arms(shapes, &quote! { labels }, &none, |shape| {
+        let DiagnosticFields {
+            labels, redactions, ..
+        } = DiagnosticFields::new(shape.fields)?;
+        if labels.is_empty() {
+            return Ok(None);
+        }
+
+        let labels = labels.iter().map(|Label { binding, text }| {
+            let text = match text {
+                Some(text) => unless_minimal(
+                    &quote! { None },
+                    &quote! { Some(::easyerr::__private::format!(#text)) },
+                ),
+                None => quote! { None },
+            };
+
+            quote! {
+                ::easyerr::__private::miette::LabeledSpan::new_with_span(
+                    #text,
+                    ::core::clone::Clone::clone(#binding),
+                )
+            }
+        });
+
+        Ok(Some(quote! {
+            #redactions
+            Some(::easyerr::__private::Box::new([#(#labels),*].into_iter()))
+        }))
+    })?;
+    let related_arms = method_arms(shapes, &quote! { related }, &none, |_| Ok(None))?;
+    let diagnostic_source_arms =
+        method_arms(shapes, &quote! { diagnostic_source }, &none, |shape| {
+            // the source of a transparent error is the source of its source
+            if shape.attrs.is_transparent() || source_field_of(shape.fields.iter()).is_none() {
+                return Ok(None);
+            }
+
+            Ok(Some(source_as_trait(shape, "DiagnosticRef", "diagnostic")))
+        })?;
+
+    let display = quote! { dyn ::core::fmt::Display + 'a };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let traits = quote! {
+        #[allow(unused_imports)]
+        use ::easyerr::__private::{NoDiagnostic as _, ViaDiagnostic as _};
+    };
+
+    Ok(quote! {
+        #[allow(unused_variables, unexpected_cfgs)]
+        impl #impl_generics ::easyerr::__private::miette::Diagnostic for #ty_ident #ty_generics #where_clause {
+            fn code<'a>(&'a self) -> Option<::easyerr::__private::Box<#display>> {
+                #traits
+
+                match self {
+                    #(#code_arms),*
+                }
+            }
+
+            fn severity(&self) -> Option<::easyerr::__private::miette::Severity> {
+                #traits
+
+                match self {
+                    #(#severity_arms),*
+                }
+            }
+
+            fn help<'a>(&'a self) -> Option<::easyerr::__private::Box<#display>> {
+                #traits
+
+                match self {
+                    #(#help_arms),*
+                }
+            }
+
+            fn url<'a>(&'a self) -> Option<::easyerr::__private::Box<#display>> {
+                #traits
+
+                match self {
+                    #(#url_arms),*
+                }
+            }
+
+            fn source_code(&self) -> Option<&dyn ::easyerr::__private::miette::SourceCode> {
+                #traits
+
+                match self {
+                    #(#source_code_arms),*
+                }
+            }
+
+            fn labels(
+                &self,
+            ) -> Option<
+                ::easyerr::__private::Box<
+                    dyn Iterator<Item = ::easyerr::__private::miette::LabeledSpan> + '_,
+                >,
+            > {
+                #traits
+
+                match self {
+                    #(#labels_arms),*
+                }
+            }
+
+            fn related<'a>(
+                &'a self,
+            ) -> Option<
+                ::easyerr::__private::Box<
+                    dyn Iterator<Item = &'a dyn ::easyerr::__private::miette::Diagnostic> + 'a,
+                >,
+            > {
+                #traits
+
+                match self {
+                    #(#related_arms),*
+                }
+            }
+
+            fn diagnostic_source(&self) -> Option<&dyn ::easyerr::__private::miette::Diagnostic> {
+                #traits
+
+                match self {
+                    #(#diagnostic_source_arms),*
+                }
+            }
+        }
+    })
+}
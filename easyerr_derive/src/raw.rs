@@ -0,0 +1,153 @@
+use crate::{source_as_dyn, source_field_of, source_method, Shape};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use std::collections::HashSet;
+use syn::{spanned::Spanned, Error, Expr, ExprLit, ExprUnary, Generics, Ident, Lit, UnOp};
+
+const MISSING_ERRNO: &str = "missing `errno` argument. when a type has raw error codes, every \
+                             non-transparent struct or variant needs one, either through `errno` \
+                             or an explicit discriminant.";
+
+/// Value of the integer literal `expr`, which may be negated.
+fn literal_value(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => int.base10_parse().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => literal_value(expr).map(|value| -value),
+        _ => None,
+    }
+}
+
+/// Discriminant of each shape, given its explicit discriminant, if any. Like in Rust, shapes
+/// without one count up from the previous shape, starting at `0`. The value of each discriminant is
+/// known if it is (or counts up from) an integer literal.
+///
+/// Discriminants only exist on enums with at least one explicit discriminant, so this is empty
+/// otherwise.
+pub fn discriminants(explicit: &[Option<&Expr>]) -> Vec<(TokenStream, Option<i64>)> {
+    if explicit.iter().all(Option::is_none) {
+        return Vec::new();
+    }
+
+    let mut previous: Option<(TokenStream, Option<i64>)> = None;
+    explicit
+        .iter()
+        .map(|explicit| {
+            let discriminant = match (explicit, previous.take()) {
+                (Some(expr), _) => (expr.to_token_stream(), literal_value(expr)),
+                (None, Some((tokens, value))) => {
+                    (quote! { (#tokens) + 1 }, value.map(|value| value + 1))
+                }
+                (None, None) => (quote! { 0 }, Some(0)),
+            };
+
+            previous = Some(discriminant.clone());
+            discriminant
+        })
+        .collect()
+}
+
+/// Generates a `RawError` impl for the type, if any of its shapes has an `errno` or an explicit
+/// discriminant (`discriminants` holds the explicit discriminant of each shape, if any).
+///
+/// Every shape must either have a raw code, through its `errno` or its explicit or implicit
+/// discriminant, or be transparent, in which case the raw code of the source is used. Raw codes
+/// must be unique, as far as their values are known.
+pub fn generate_raw_error_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    shapes: &[Shape],
+    explicit: &[Option<&Expr>],
+) -> syn::Result<Option<TokenStream>> {
+    if shapes.iter().all(|s| s.attrs.errno.is_none()) && explicit.iter().all(Option::is_none) {
+        return Ok(None);
+    }
+
+    let implicit = discriminants(explicit);
+
+    let mut seen = HashSet::new();
+    let mut as_raw_arms = Vec::new();
+    let mut from_raw_arms = Vec::new();
+    let mut os_error_arms = Vec::new();
+    for (i, shape) in shapes.iter().enumerate() {
+        let path = &shape.path;
+        let raw = match (&shape.attrs.errno, explicit[i], implicit.get(i)) {
+            (Some(errno), ..) => Some((
+                errno.to_token_stream(),
+                Some(i64::from(errno.base10_parse::<i32>()?)),
+                errno.span(),
+            )),
+            (None, Some(expr), _) => {
+                Some((expr.to_token_stream(), literal_value(expr), expr.span()))
+            }
+            // transparent shapes prefer the raw code of their source over an implicit discriminant
+            (None, None, Some((tokens, value))) if !shape.attrs.is_transparent() => {
+                Some((tokens.clone(), *value, shape.ident.span()))
+            }
+            _ => None,
+        };
+
+        if let Some((_, Some(value), span)) = &raw {
+            if !seen.insert(*value) {
+                return Err(Error::new(
+                    *span,
+                    format!("duplicate raw error code `{value}`"),
+                ));
+            }
+        }
+
+        let raw = raw.map(|(tokens, ..)| tokens);
+
+        as_raw_arms.push(match &raw {
+            Some(raw) => quote! { #path { .. } => #raw },
+            None if shape.attrs.is_transparent() => {
+                let raw = source_method(shape, &quote! { ::easyerr::RawError::as_raw }, "errno")?;
+                quote! { #path { source, .. } => #raw }
+            }
+            None => return Err(Error::new(shape.ident.span(), MISSING_ERRNO)),
+        });
+
+        if let Some(raw) = raw.filter(|_| shape.fields.is_empty()) {
+            from_raw_arms.push(quote! { raw if raw == (#raw) => Some(#path {}) });
+        }
+
+        os_error_arms.push(match source_field_of(shape.fields.iter()) {
+            Some(field) => {
+                let source = source_as_dyn(field, quote! { source })?;
+                quote! {
+                    #path { source, .. } => #source.and_then(::easyerr::__private::raw_os_error)
+                }
+            }
+            None => quote! { #path { .. } => None },
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(Some(quote! {
+        impl #impl_generics ::easyerr::RawError for #ty_ident #ty_generics #where_clause {
+            fn as_raw(&self) -> i32 {
+                match self {
+                    #(#as_raw_arms),*
+                }
+            }
+
+            fn from_raw(raw: i32) -> Option<Self> {
+                match raw {
+                    #(#from_raw_arms,)*
+                    _ => None,
+                }
+            }
+
+            fn raw_os_error(&self) -> Option<i32> {
+                match self {
+                    #(#os_error_arms),*
+                }
+            }
+        }
+    }))
+}
@@ -0,0 +1,108 @@
+use crate::{attr::ErrorAttrs, exposed_fields, fields::field_traits, ExposedField, Shape};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Generics, Ident};
+
+fn generate_fields_arm(shape: &Shape) -> syn::Result<TokenStream> {
+    let pattern = shape.pattern();
+    let fields = exposed_fields(shape.fields)?;
+    let entries = fields.into_iter().filter(|f| f.serialize).map(|field| {
+        let ExposedField {
+            key,
+            binding,
+            redact,
+            ..
+        } = field;
+
+        match redact {
+            Some(mode) => quote! {
+                ::easyerr::__private::serialize_redacted(
+                    __map,
+                    #key,
+                    #binding,
+                    &(&&&&&::easyerr::__private::FieldRef(#binding)).field(),
+                    #mode,
+                )?;
+            },
+            None => quote! {
+                ::easyerr::__private::serde::ser::SerializeMap::serialize_entry(__map, #key, #binding)?;
+            },
+        }
+    });
+
+    Ok(quote! {
+        #pattern => {
+            #(#entries)*
+        }
+    })
+}
+
+/// Generates a `Serialize` impl for the type if it has `#[error(serialize)]`. The impl emits the
+/// kind, message, fields and chain of sources of the error.
+pub fn generate_serialize_impl(
+    ty_ident: &Ident,
+    generics: &Generics,
+    attrs: &ErrorAttrs,
+    shapes: &[Shape],
+    is_enum: bool,
+) -> syn::Result<TokenStream> {
+    if attrs.serialize != Some(true) {
+        return Ok(TokenStream::new());
+    }
+
+    if !cfg!(feature = "serde") {
+        return Err(Error::new(
+            ty_ident.span(),
+            "`serialize` requires the `serde` feature of easyerr",
+        ));
+    }
+
+    let kind_arms = shapes.iter().map(|shape| {
+        let path = &shape.path;
+        let kind = if is_enum {
+            format!("{}::{}", ty_ident, shape.ident)
+        } else {
+            ty_ident.to_string()
+        };
+
+        quote! { #path { .. } => #kind }
+    });
+
+    let fields_arms = shapes
+        .iter()
+        .map(generate_fields_arm)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let traits = field_traits();
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::easyerr::__private::serde::Serialize for #ty_ident #ty_generics #where_clause {
+            fn serialize<__S>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+            where
+                __S: ::easyerr::__private::serde::Serializer,
+            {
+                let kind = match self {
+                    #(#kind_arms),*
+                };
+
+                ::easyerr::__private::serialize_error(serializer, kind, self)
+            }
+        }
+
+        impl #impl_generics ::easyerr::__private::SerializeFields for #ty_ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn serialize_fields<__M>(&self, __map: &mut __M) -> ::core::result::Result<(), __M::Error>
+            where
+                __M: ::easyerr::__private::serde::ser::SerializeMap,
+            {
+                #traits
+
+                match self {
+                    #(#fields_arms)*
+                }
+
+                Ok(())
+            }
+        }
+    })
+}
@@ -1,21 +1,41 @@
-use crate::{extract_source_field, generics_required_by_type, source_field_of, ErrorAttrArg};
+use crate::{
+    attr::{Location, Message},
+    boxed::generate_size_assertion,
+    catalog::generate_catalog_entries,
+    code::generate_error_code_impl,
+    code_prefix, context_source,
+    debug::generate_debug_impl,
+    defmt::generate_defmt_impl,
+    exit::generate_exit_code_impl,
+    extract_source_field,
+    fields::generate_error_fields_impl,
+    generate_leaf_selector, generics_required_by_type,
+    hints::generate_hints_impl,
+    http::generate_http_error_impl,
+    io::generate_io_error_impl,
+    is_optional_source,
+    log::generate_log_error_impl,
+    miette::generate_diagnostic_impl,
+    raw::generate_raw_error_impl,
+    ser::generate_serialize_impl,
+    source_as_dyn, source_field_of, write_message, write_transparent, Shape,
+};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use std::collections::HashSet;
-use syn::{parse_quote, spanned::Spanned, Error, Fields, Generics, ItemStruct, Visibility};
+use syn::{parse_quote, spanned::Spanned, Error, Generics, ItemStruct, Visibility};
 
 fn generate_ctx(struct_: &ItemStruct) -> Result<TokenStream, Error> {
-    let (mut ctx_fields, Some(source_field_ty)) = extract_source_field(struct_.fields.iter())
-    else {
+    let (mut ctx_fields, Some(source_field)) = extract_source_field(struct_.fields.iter()) else {
         return Err(Error::new(
             struct_.span(),
             "can't generate context for struct without source",
         ));
     };
 
-    ctx_fields
-        .iter_mut()
-        .for_each(|f| f.vis = Visibility::Public(syn::token::Pub { span: f.vis.span() }));
+    for f in &mut ctx_fields {
+        f.vis = Visibility::Public(syn::token::Pub { span: f.vis.span() });
+    }
 
     let used_generics = struct_
         .fields
@@ -35,7 +55,11 @@ fn generate_ctx(struct_: &ItemStruct) -> Result<TokenStream, Error> {
     let (ctx_impl_generics, ctx_ty_generics, ctx_where_clause) = used_generics.split_for_impl();
 
     let ty_ident = &struct_.ident;
-    let (ty_impl_generics, ty_ty_generics, ty_where_clause) = struct_.generics.split_for_impl();
+    let (_, ty_ty_generics, _) = struct_.generics.split_for_impl();
+    let source = context_source(&source_field, &struct_.generics)?;
+    let (impl_generics, _, where_clause) = source.generics.split_for_impl();
+    let source_ty = &source.ty;
+    let store = &source.store;
 
     let struct_def = quote! {
         struct #ctx_ident #ctx_impl_generics #ctx_where_clause {
@@ -43,119 +67,129 @@ fn generate_ctx(struct_: &ItemStruct) -> Result<TokenStream, Error> {
         }
     };
 
-    let ctx_fields_extract = ctx_fields.iter().map(|f| {
-        let f_name = &f.ident;
-        quote! {
-            #f_name: self.#f_name
-        }
-    });
+    let ctx_fields_extract = ctx_fields
+        .iter()
+        .map(|f| {
+            let f_name = &f.ident;
+            quote! {
+                #f_name: self.#f_name
+            }
+        })
+        .collect::<Vec<_>>();
 
     let ctx_impl = quote! {
-        impl #ty_impl_generics ::easyerr::ErrorContext for #ctx_ident #ctx_ty_generics #ty_where_clause {
-            type Source = #source_field_ty;
+        impl #impl_generics ::easyerr::ErrorContext<#source_ty> for #ctx_ident #ctx_ty_generics #where_clause {
             type Err = #ty_ident #ty_ty_generics;
 
-            #[inline(always)]
-            fn add_to_source(self, source: Self::Source) -> #ty_ident #ty_ty_generics {
+            #[cold]
+            fn add_to_source(self, source: #source_ty) -> #ty_ident #ty_ty_generics {
                 #ty_ident {
-                    source,
+                    source: #store,
                     #(#ctx_fields_extract),*
                 }
             }
         }
     };
 
+    let leaf_selector = is_optional_source(&source_field).then(|| {
+        let leaf_ident = format_ident!("{}Leaf", ctx_ident_str);
+        generate_leaf_selector(
+            &quote! {
+                struct #leaf_ident #ctx_impl_generics #ctx_where_clause {
+                    #(#ctx_fields),*
+                }
+            },
+            &quote! { #leaf_ident #ctx_ty_generics },
+            &struct_.generics,
+            &quote! { #ty_ident #ty_ty_generics },
+            &quote! {
+                #ty_ident {
+                    source: ::core::option::Option::None,
+                    #(#ctx_fields_extract),*
+                }
+            },
+        )
+    });
+
     Ok(quote! {
         #struct_def
         #ctx_impl
+        #leaf_selector
     })
 }
 
-fn generate_struct_display_impl(struct_: &ItemStruct) -> Result<TokenStream, Error> {
-    let struct_display_attr = struct_
-        .attrs
-        .iter()
-        .find(|a| a.path().is_ident("error"))
-        .ok_or(Error::new(
-            struct_.span(),
-            "struct is missing #[error(...)] attribute",
-        ))?;
-
-    let display_attr_arg = struct_display_attr.parse_args::<ErrorAttrArg>()?;
-    let display = match display_attr_arg {
-        ErrorAttrArg::Str(struct_display_str) => match &struct_.fields {
-            Fields::Named(f) => {
-                let fields = f.named.iter().map(|f| &f.ident);
-                quote! {
-                    let Self{ #(#fields),* } = self;
-                    write!(f, #struct_display_str)
-                }
-            }
-            Fields::Unnamed(f) => {
-                let fields = f
-                    .unnamed
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| format_ident!("f{i}"));
-                quote! {
-                    let Self(#(#fields),*) = self;
-                    write!(f, #struct_display_str)
-                }
-            }
-            Fields::Unit => {
-                quote! {
-                    write!(f, #struct_display_str)
-                }
+fn generate_struct_display_impl(struct_: &ItemStruct, shape: &Shape) -> Result<TokenStream, Error> {
+    let prefix = code_prefix(shape, shape.attrs.show_code);
+    let display = match &shape.attrs.message {
+        Some(Message::Format(struct_display_str)) => {
+            let pattern = shape.pattern();
+            let message = write_message(
+                shape,
+                &struct_.ident.to_string(),
+                struct_display_str,
+                &prefix,
+                None,
+            )?;
+            quote! {
+                let #pattern = self;
+                #message
             }
-        },
-        ErrorAttrArg::Transparent => {
+        }
+        Some(Message::Transparent(span)) => {
             if source_field_of(struct_.fields.iter()).is_none() {
                 return Err(Error::new(
-                    struct_display_attr.span(),
+                    *span,
                     "can't use `transparent` display on a struct with no source field",
                 ));
             }
 
+            let message =
+                write_transparent(shape, &struct_.ident.to_string(), &quote! { &self.source });
             quote! {
-                self.source.fmt(f)
+                #prefix
+                #message
             }
         }
+        None => {
+            return Err(Error::new(
+                struct_.span(),
+                "struct is missing #[error(...)] attribute",
+            ))
+        }
     };
 
     let struct_ident = &struct_.ident;
     let (impl_generics, ty_generics, where_clause) = struct_.generics.split_for_impl();
     Ok(quote! {
         impl #impl_generics core::fmt::Display for #struct_ident #ty_generics #where_clause {
-            #[allow(unused_variables)]
+            #[allow(unused_variables, unexpected_cfgs)]
             fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
                 #display
+
+                if f.alternate() {
+                    ::easyerr::__private::fmt_hints(f, self, ::core::error::Error::source(self))?;
+                }
+
+                Ok(())
             }
         }
     })
 }
 
-fn generate_struct_error_impl(struct_: &ItemStruct) -> Result<TokenStream, Error> {
+fn generate_struct_error_impl(struct_: &ItemStruct, shape: &Shape) -> Result<TokenStream, Error> {
     let ty_ident = &struct_.ident;
     let (impl_generics, ty_generics, where_clause) = struct_.generics.split_for_impl();
 
-    let source = if source_field_of(struct_.fields.iter()).is_some() {
-        let error_attr = struct_
-            .attrs
-            .iter()
-            .find(|a| a.path().is_ident("error"))
-            .ok_or(Error::new(
-                struct_.span(),
-                "struct is missing #[error(...)] attribute",
-            ))?;
-        let error_attr_arg = error_attr.parse_args::<ErrorAttrArg>()?;
-
-        if error_attr_arg == ErrorAttrArg::Transparent {
-            quote! { self.source.source() }
-        } else {
-            quote! { Some(&self.source) }
+    let source = match source_field_of(struct_.fields.iter()) {
+        Some(field) => {
+            let source = source_as_dyn(field, quote! { &self.source })?;
+            if shape.attrs.is_transparent() {
+                quote! { #source.and_then(::core::error::Error::source) }
+            } else {
+                source
+            }
         }
-    } else {
-        quote! { None }
+        None => quote! { None },
     };
 
     Ok(quote! {
@@ -168,16 +202,82 @@ fn generate_struct_error_impl(struct_: &ItemStruct) -> Result<TokenStream, Error
 }
 
 pub fn derive_err_struct(struct_: &ItemStruct) -> Result<TokenStream, Error> {
+    let shape = Shape::new(
+        quote! { Self },
+        &struct_.ident,
+        &struct_.fields,
+        &struct_.attrs,
+        Location::Struct,
+    )?;
+    let shapes = std::slice::from_ref(&shape);
+
     let ctx_struct = source_field_of(struct_.fields.iter())
         .is_some()
         .then(|| generate_ctx(struct_))
         .transpose()?;
-    let display_impl = generate_struct_display_impl(struct_)?;
-    let error_impl = generate_struct_error_impl(struct_)?;
+    let display_impl = generate_struct_display_impl(struct_, &shape)?;
+    let error_impl = generate_struct_error_impl(struct_, &shape)?;
+    let code_impl = generate_error_code_impl(&struct_.ident, &struct_.generics, shapes)?;
+    if shape.attrs.show_code && code_impl.is_none() {
+        return Err(Error::new(
+            struct_.ident.span(),
+            "`show_code` requires the struct to have a `code`",
+        ));
+    }
+
+    let exit_code_impl = generate_exit_code_impl(&struct_.ident, &struct_.generics, shapes);
+    let raw_impl = generate_raw_error_impl(&struct_.ident, &struct_.generics, shapes, &[None])?;
+    let io_impl = generate_io_error_impl(
+        &struct_.ident,
+        &struct_.generics,
+        &shape.attrs,
+        shapes,
+        false,
+    )?;
+    let hints_impl = generate_hints_impl(&struct_.ident, &struct_.generics, shapes)?;
+    let fields_impl = generate_error_fields_impl(&struct_.ident, &struct_.generics, shapes)?;
+    let log_impl = generate_log_error_impl(&struct_.ident, &struct_.generics, shapes)?;
+    let http_impl = generate_http_error_impl(&struct_.ident, &struct_.generics, shapes)?;
+    let diagnostic_impl =
+        generate_diagnostic_impl(&struct_.ident, &struct_.generics, &shape.attrs, shapes)?;
+    let defmt_impl = generate_defmt_impl(&struct_.ident, &struct_.generics, &shape.attrs, shapes)?;
+    let serialize_impl = generate_serialize_impl(
+        &struct_.ident,
+        &struct_.generics,
+        &shape.attrs,
+        shapes,
+        false,
+    )?;
+    let catalog_entries = generate_catalog_entries(&struct_.ident, false, shapes);
+    let size_assertion = shape
+        .attrs
+        .max_size
+        .as_ref()
+        .map(|max_size| generate_size_assertion(&struct_.ident, &struct_.generics, max_size))
+        .transpose()?;
+    let debug_impl = shape
+        .attrs
+        .debug
+        .map(|mode| generate_debug_impl(&struct_.ident, &struct_.generics, shapes, mode))
+        .transpose()?;
 
     Ok(quote! {
         #ctx_struct
+        #size_assertion
         #display_impl
         #error_impl
+        #code_impl
+        #http_impl
+        #log_impl
+        #fields_impl
+        #hints_impl
+        #exit_code_impl
+        #raw_impl
+        #io_impl
+        #debug_impl
+        #defmt_impl
+        #diagnostic_impl
+        #catalog_entries
+        #serialize_impl
     })
 }
@@ -0,0 +1,62 @@
+use easyerr::*;
+
+#[derive(Debug, Error)]
+#[error("inner failed")]
+struct InnerError;
+
+#[derive(Debug, Error)]
+enum MiddleError {
+    #[error("middle failed with {code}")]
+    Inner { source: InnerError, code: u32 },
+    #[error(transparent)]
+    Io { source: std::io::Error },
+}
+
+#[derive(Debug, Error)]
+#[error("outer failed")]
+struct OuterError {
+    source: MiddleError,
+}
+
+#[derive(Debug, Error)]
+#[error(transparent)]
+struct TransparentError {
+    source: MiddleError,
+}
+
+fn outer() -> OuterError {
+    Err::<(), _>(InnerError)
+        .context(MiddleCtx::Inner { code: 7 })
+        .context(OuterCtx {})
+        .unwrap_err()
+}
+
+#[test]
+fn alternate_prints_chain() {
+    let e = outer();
+    assert_eq!(e.to_string(), "outer failed");
+    assert_eq!(
+        format!("{e:#}"),
+        "outer failed: middle failed with 7: inner failed"
+    );
+    assert_eq!(
+        format!("{:#}", e.source),
+        "middle failed with 7: inner failed"
+    );
+}
+
+#[test]
+fn alternate_transparent_does_not_repeat() {
+    let e = Err::<(), _>(InnerError)
+        .context(MiddleCtx::Inner { code: 1 })
+        .context(TransparentCtx {})
+        .unwrap_err();
+    assert_eq!(e.to_string(), "middle failed with 1");
+    assert_eq!(format!("{e:#}"), "middle failed with 1: inner failed");
+
+    let e = Err::<(), _>(std::io::Error::other("disk on fire"))
+        .context(MiddleCtx::Io)
+        .unwrap_err();
+    assert_eq!(format!("{e}"), "disk on fire");
+    assert_eq!(format!("{e:#}"), "disk on fire");
+}
@@ -0,0 +1,74 @@
+use std::error::Error as StdError;
+
+use easyerr::*;
+
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+#[derive(Debug, Error)]
+#[error("not found")]
+struct NotFoundError;
+
+#[derive(Debug, Error)]
+enum PluginError {
+    #[error("plugin {name} failed")]
+    Plugin {
+        source: Box<dyn StdError + Send + Sync>,
+        name: String,
+    },
+    #[error(transparent)]
+    Other {
+        #[error(source(boxed))]
+        source: BoxError,
+    },
+}
+
+#[derive(Debug, Error)]
+#[error("failed to load")]
+struct LoadError {
+    source: Box<dyn StdError>,
+}
+
+#[derive(Debug, Error)]
+enum TaskError {
+    #[error("task timed out", code = "E_TIMEOUT", exit_code = 75)]
+    Timeout,
+    #[error(transparent, code = "E_TASK")]
+    Task {
+        #[error(source(boxed))]
+        source: BoxError,
+    },
+}
+
+#[test]
+fn forwarding() {
+    let e = Err::<(), _>(NotFoundError)
+        .context(TaskCtx::Task)
+        .unwrap_err();
+    assert_eq!(e.code(), "E_TASK");
+    assert_eq!(e.exit_code(), 1);
+    assert_eq!(TaskError::Timeout.code(), "E_TIMEOUT");
+    assert_eq!(TaskError::Timeout.exit_code(), 75);
+}
+
+#[test]
+fn main() {
+    let e = Err::<(), _>(std::io::Error::new(std::io::ErrorKind::NotFound, "oops"))
+        .context(PluginCtx::Plugin { name: "fmt".into() })
+        .unwrap_err();
+    assert_eq!(format!("{e:#}"), "plugin fmt failed: oops");
+    assert!(e.source().unwrap().is::<std::io::Error>());
+
+    let e = Err::<(), _>(NotFoundError)
+        .context(PluginCtx::Other)
+        .unwrap_err();
+    assert_eq!(e.to_string(), "not found");
+    assert!(e.source().is_none());
+
+    let boxed: BoxError = Box::new(NotFoundError);
+    let e = Err::<(), _>(boxed).context(PluginCtx::Other).unwrap_err();
+    assert_eq!(e.to_string(), "not found");
+
+    let e = Err::<(), _>(NotFoundError).context(LoadCtx {}).unwrap_err();
+    assert_eq!(format!("{e:#}"), "failed to load: not found");
+    assert!(e.source().unwrap().is::<NotFoundError>());
+}
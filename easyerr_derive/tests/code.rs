@@ -0,0 +1,88 @@
+use easyerr::*;
+
+#[derive(Debug, Error)]
+#[error("config file is empty", code = "E0001", show_code)]
+struct EmptyConfigError;
+
+#[derive(Debug, Error)]
+#[error(show_code)]
+enum ConfigError {
+    #[error("failed to read config", code = "E0002")]
+    Read { source: std::io::Error },
+    #[error("invalid key {f0}", code = "E0003")]
+    InvalidKey(String),
+    #[error(transparent)]
+    Empty { source: EmptyConfigError },
+}
+
+#[derive(Debug, Error)]
+#[error("uncoded")]
+struct UncodedError;
+
+#[derive(Debug, Error)]
+#[error(show_code)]
+enum WrapperError {
+    #[error(transparent, code = "W1")]
+    Uncoded { source: UncodedError },
+    #[error(transparent, code = "W2")]
+    Empty { source: EmptyConfigError },
+}
+
+#[derive(Debug, Error)]
+enum QuietError {
+    #[error("quiet", code = "Q1")]
+    Quiet,
+}
+
+#[test]
+fn code() {
+    assert_eq!(EmptyConfigError.code(), "E0001");
+    assert_eq!(ConfigError::InvalidKey("a".into()).code(), "E0003");
+    assert_eq!(
+        ConfigError::Read {
+            source: std::io::Error::other("oops")
+        }
+        .code(),
+        "E0002"
+    );
+    assert_eq!(
+        ConfigError::Empty {
+            source: EmptyConfigError
+        }
+        .code(),
+        "E0001"
+    );
+    assert_eq!(QuietError::Quiet.code(), "Q1");
+}
+
+#[test]
+fn transparent_own_code() {
+    let e = WrapperError::Uncoded {
+        source: UncodedError,
+    };
+    assert_eq!(e.code(), "W1");
+    assert_eq!(e.to_string(), "[W1] uncoded");
+
+    let e = WrapperError::Empty {
+        source: EmptyConfigError,
+    };
+    assert_eq!(e.code(), "W2");
+    assert_eq!(e.to_string(), "[W2] [E0001] config file is empty");
+}
+
+#[test]
+fn display_prefix() {
+    assert_eq!(EmptyConfigError.to_string(), "[E0001] config file is empty");
+    assert_eq!(
+        ConfigError::InvalidKey("a".into()).to_string(),
+        "[E0003] invalid key a"
+    );
+    assert_eq!(
+        ConfigError::Empty {
+            source: EmptyConfigError
+        }
+        .to_string(),
+        "[E0001] config file is empty"
+    );
+    assert_eq!(QuietError::Quiet.to_string(), "quiet");
+}
@@ -0,0 +1,117 @@
+use easyerr::*;
+use std::fmt::{Debug, Display};
+
+struct NotPrintable;
+
+#[derive(Debug, Error)]
+enum UploadError {
+    #[error("file {name} is too large ({size} bytes)")]
+    TooLarge {
+        name: String,
+        size: u64,
+        #[error(redact)]
+        owner: String,
+        chunks: Vec<u32>,
+    },
+    #[error("upload {f0} was rejected")]
+    Rejected(u32, #[error(skip)] String, #[error(serialize = false)] u8),
+    #[error("failed to write {path}")]
+    Write {
+        source: std::io::Error,
+        path: String,
+    },
+    #[error(transparent)]
+    Other { source: std::fmt::Error },
+}
+
+#[derive(Error)]
+#[error("unprintable")]
+struct UnprintableError {
+    id: u8,
+    inner: NotPrintable,
+}
+
+impl Debug for UnprintableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("UnprintableError")
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("visit by {visitor} was refused")]
+struct VisitError {
+    visitor: String,
+}
+
+#[derive(Default)]
+struct Collect(Vec<String>);
+
+impl FieldVisitor for Collect {
+    fn record_display(&mut self, name: &'static str, value: &dyn Display) {
+        self.0.push(format!("{name}={value}"));
+    }
+
+    fn record_debug(&mut self, name: &'static str, value: &dyn Debug) {
+        self.0.push(format!("{name}~{value:?}"));
+    }
+
+    fn record_u64(&mut self, name: &'static str, value: u64) {
+        self.0.push(format!("{name}:{value}"));
+    }
+}
+
+fn fields(err: &dyn ErrorFields) -> Vec<String> {
+    let mut collect = Collect::default();
+    err.visit_fields(&mut collect);
+    collect.0
+}
+
+#[test]
+fn visit_fields() {
+    let err = UploadError::TooLarge {
+        name: "a.bin".into(),
+        size: 1024,
+        owner: "bob".into(),
+        chunks: vec![1, 2],
+    };
+    assert_eq!(
+        fields(&err),
+        [
+            "name=a.bin",
+            "size:1024",
+            "owner=<redacted>",
+            "chunks~[1, 2]"
+        ]
+    );
+    assert_eq!(unredacted(|| fields(&err))[2], "owner=bob");
+
+    assert_eq!(
+        fields(&UploadError::Rejected(3, "spam".into(), 1)),
+        ["0:3", "2:1"]
+    );
+    assert_eq!(
+        fields(&UploadError::Write {
+            source: std::io::Error::other("disk full"),
+            path: "/tmp".into()
+        }),
+        ["path=/tmp"]
+    );
+    assert!(fields(&UploadError::Other {
+        source: std::fmt::Error
+    })
+    .is_empty());
+
+    let err = UnprintableError {
+        id: 1,
+        inner: NotPrintable,
+    };
+    assert_eq!(fields(&err), ["id:1"]);
+}
+
+#[test]
+fn field_named_visitor() {
+    let err = VisitError {
+        visitor: "bob".into(),
+    };
+    assert_eq!(fields(&err), ["visitor=bob"]);
+}
@@ -0,0 +1,128 @@
+use easyerr::*;
+
+#[derive(Debug, Error)]
+#[error(
+    "failed to open {path}",
+    help = "check that {path} exists",
+    note = "paths are relative to the working directory",
+    note = "symlinks are not followed"
+)]
+struct OpenError {
+    path: String,
+    source: std::io::Error,
+}
+
+#[derive(Debug, Error)]
+enum StartError {
+    #[error("failed to load config", help = "run `init` to create a config")]
+    Config { source: OpenError },
+    #[error("invalid token {token}", help = "tokens look like {token}")]
+    Token {
+        #[error(redact)]
+        token: String,
+    },
+    #[error(transparent)]
+    Open { source: OpenError },
+    #[error(
+        "unknown key {0}",
+        help = "remove {} from line {1}",
+        note = "{0:?} is not a key"
+    )]
+    UnknownKey(#[error(redact)] String, u32),
+}
+
+fn open() -> OpenError {
+    OpenError {
+        path: "app.toml".into(),
+        source: std::io::Error::other("not found"),
+    }
+}
+
+#[test]
+fn hints() {
+    let err = open();
+    assert_eq!(
+        err.help().unwrap().to_string(),
+        "check that app.toml exists"
+    );
+    assert_eq!(
+        err.notes().map(|n| n.to_string()).collect::<Vec<_>>(),
+        [
+            "paths are relative to the working directory",
+            "symlinks are not followed"
+        ]
+    );
+
+    let err = StartError::Open { source: open() };
+    assert_eq!(
+        err.help().unwrap().to_string(),
+        "check that app.toml exists"
+    );
+    assert_eq!(err.notes().count(), 2);
+
+    let err = StartError::Token {
+        token: "hunter2".into(),
+    };
+    assert_eq!(
+        err.help().unwrap().to_string(),
+        "tokens look like <redacted>"
+    );
+    assert_eq!(
+        unredacted(|| err.help().unwrap().to_string()),
+        "tokens look like hunter2"
+    );
+    assert_eq!(
+        format!("{:+}", err.help().unwrap()),
+        "tokens look like hunter2"
+    );
+    assert_eq!(err.notes().count(), 0);
+}
+
+#[test]
+fn positional() {
+    let err = StartError::UnknownKey("port".into(), 3);
+    assert_eq!(
+        err.help().unwrap().to_string(),
+        "remove <redacted> from line 3"
+    );
+    assert_eq!(
+        format!("{:+}", err.help().unwrap()),
+        "remove port from line 3"
+    );
+    assert_eq!(
+        err.notes().map(|n| n.to_string()).collect::<Vec<_>>(),
+        ["<redacted> is not a key"]
+    );
+    assert_eq!(
+        format!("{err:+#}"),
+        "unknown key port\n\
+         help: remove port from line 3\n\
+         note: \"port\" is not a key"
+    );
+}
+
+#[test]
+fn alternate() {
+    let err = StartError::Config { source: open() };
+    assert_eq!(err.to_string(), "failed to load config");
+    assert_eq!(
+        format!("{err:#}"),
+        "failed to load config\n\
+         help: run `init` to create a config\n\
+         caused by: failed to open app.toml\n\
+         help: check that app.toml exists\n\
+         note: paths are relative to the working directory\n\
+         note: symlinks are not followed\n\
+         caused by: not found"
+    );
+
+    let err = StartError::Open { source: open() };
+    assert_eq!(
+        format!("{err:#}"),
+        "failed to open app.toml\n\
+         help: check that app.toml exists\n\
+         note: paths are relative to the working directory\n\
+         note: symlinks are not followed\n\
+         caused by: not found"
+    );
+}
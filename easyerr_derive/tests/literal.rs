@@ -0,0 +1,23 @@
+use easyerr::*;
+
+#[derive(Debug, Error)]
+#[error("expected {{ or }}")]
+pub struct BraceError;
+
+#[derive(Debug, Error)]
+enum TemplateError {
+    #[error("empty placeholder {{}}")]
+    Empty,
+    #[error("}}")]
+    Closing,
+    #[error("unclosed {{ at {f0}")]
+    Unclosed(usize),
+}
+
+#[test]
+fn braces() {
+    assert_eq!(BraceError.to_string(), "expected { or }");
+    assert_eq!(TemplateError::Empty.to_string(), "empty placeholder {}");
+    assert_eq!(TemplateError::Closing.to_string(), "}");
+    assert_eq!(TemplateError::Unclosed(3).to_string(), "unclosed { at 3");
+}
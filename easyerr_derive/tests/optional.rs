@@ -0,0 +1,87 @@
+use std::{error::Error as _, io};
+
+use easyerr::*;
+
+#[derive(Debug, Error)]
+#[error("timed out after {secs}s")]
+struct TimeoutError {
+    source: Option<io::Error>,
+    secs: u64,
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "not found",
+    help = "check the spelling",
+    code = "E_NOT_FOUND",
+    exit_code = 66,
+    status = 404
+)]
+struct NotFoundError;
+
+#[derive(Debug, Error)]
+enum LookupError {
+    #[error(transparent, fallback = "key not found", code = "E_MISSING")]
+    Missing { source: Option<NotFoundError> },
+    #[error("lookup of {key} failed", code = "E_FAILED", status = 502)]
+    Failed {
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+        key: String,
+    },
+}
+
+fn refused() -> io::Error {
+    io::Error::new(io::ErrorKind::ConnectionRefused, "refused")
+}
+
+#[test]
+fn with_source() {
+    let e = Err::<(), _>(refused())
+        .context(TimeoutCtx { secs: 5 })
+        .unwrap_err();
+    assert_eq!(format!("{e:#}"), "timed out after 5s: refused");
+    assert!(e.source().is_some());
+
+    let e = Err::<(), _>(NotFoundError)
+        .context(LookupCtx::Missing)
+        .unwrap_err();
+    assert_eq!(format!("{e:#}"), "not found\nhelp: check the spelling");
+
+    let e = Err::<(), _>(refused())
+        .context(LookupCtx::Failed { key: "a".into() })
+        .unwrap_err();
+    assert_eq!(format!("{e:#}"), "lookup of a failed: refused");
+}
+
+#[test]
+fn without_source() {
+    let e = TimeoutLeaf { secs: 5 }.build();
+    assert_eq!(format!("{e:#}"), "timed out after 5s");
+    assert!(e.source().is_none());
+
+    let e = LookupCtx::MissingLeaf.fail::<()>().unwrap_err();
+    assert_eq!(format!("{e:#}"), "key not found");
+
+    let e = LookupCtx::FailedLeaf { key: "a".into() }.build();
+    assert_eq!(format!("{e:#}"), "lookup of a failed");
+}
+
+#[test]
+fn forwarding() {
+    let e = Err::<(), _>(NotFoundError)
+        .context(LookupCtx::Missing)
+        .unwrap_err();
+    assert_eq!(e.code(), "E_MISSING");
+    assert_eq!(e.exit_code(), 66);
+    assert_eq!(e.status(), 404);
+
+    let e = LookupCtx::MissingLeaf.build();
+    assert_eq!(e.code(), "E_MISSING");
+    assert_eq!(e.exit_code(), 1);
+    assert_eq!(e.status(), 500);
+
+    let e = LookupCtx::FailedLeaf { key: "a".into() }.build();
+    assert_eq!(e.code(), "E_FAILED");
+    assert_eq!(e.exit_code(), 1);
+    assert_eq!(e.status(), 502);
+}
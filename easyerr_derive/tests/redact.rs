@@ -0,0 +1,110 @@
+use easyerr::*;
+
+#[derive(Error)]
+#[error(debug)]
+enum LoginError {
+    #[error("invalid token {token} for {user}")]
+    InvalidToken {
+        #[error(redact)]
+        token: String,
+        user: String,
+    },
+    #[error("unknown email {f0:?}")]
+    UnknownEmail(#[error(redact = "hash")] String),
+    #[error("login failed")]
+    Io { source: std::io::Error },
+}
+
+#[derive(Error)]
+#[error("failed to open {path}", debug)]
+struct OpenError {
+    #[error(redact)]
+    path: String,
+}
+
+#[test]
+fn display_redacts() {
+    let e = LoginError::InvalidToken {
+        token: "hunter2".into(),
+        user: "bob".into(),
+    };
+
+    assert_eq!(e.to_string(), "invalid token <redacted> for bob");
+    assert_eq!(format!("{e:+}"), "invalid token hunter2 for bob");
+    assert_eq!(
+        unredacted(|| e.to_string()),
+        "invalid token hunter2 for bob"
+    );
+    assert_eq!(e.to_string(), "invalid token <redacted> for bob");
+
+    let e = OpenError {
+        path: "/home/bob/secret".into(),
+    };
+    assert_eq!(e.to_string(), "failed to open <redacted>");
+    assert_eq!(format!("{e:+}"), "failed to open /home/bob/secret");
+}
+
+#[test]
+fn hash_is_stable() {
+    let a = LoginError::UnknownEmail("bob@example.com".into()).to_string();
+    let b = LoginError::UnknownEmail("bob@example.com".into()).to_string();
+    let c = LoginError::UnknownEmail("alice@example.com".into()).to_string();
+
+    assert!(a.starts_with("unknown email <redacted:"));
+    assert!(!a.contains("bob"));
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[derive(Error)]
+#[error("unknown session {session} ({session:?})", debug)]
+struct SessionError {
+    #[error(redact = "hash")]
+    session: String,
+}
+
+#[test]
+fn display_and_debug_hash_alike() {
+    let e = SessionError {
+        session: "c0ffee".into(),
+    };
+    let message = e.to_string();
+    let hash = message
+        .strip_prefix("unknown session ")
+        .and_then(|rest| rest.split_once(' '))
+        .map(|(hash, _)| hash)
+        .unwrap();
+
+    assert!(hash.starts_with("<redacted:"));
+    assert_eq!(message, format!("unknown session {hash} ({hash})"));
+    assert_eq!(
+        format!("{e:?}"),
+        format!("SessionError {{ session: {hash} }}")
+    );
+}
+
+#[test]
+fn debug_redacts() {
+    let e = LoginError::InvalidToken {
+        token: "hunter2".into(),
+        user: "bob".into(),
+    };
+    assert_eq!(
+        format!("{e:?}"),
+        r#"InvalidToken { token: <redacted>, user: "bob" }"#
+    );
+    assert_eq!(
+        format!("{e:+?}"),
+        r#"InvalidToken { token: "hunter2", user: "bob" }"#
+    );
+
+    let e = OpenError {
+        path: "/home/bob/secret".into(),
+    };
+    assert_eq!(format!("{e:?}"), "OpenError { path: <redacted> }");
+
+    let e = LoginError::Io {
+        source: std::io::Error::other("oops"),
+    };
+    assert!(format!("{e:?}").starts_with("Io { source: "));
+}
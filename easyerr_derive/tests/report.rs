@@ -0,0 +1,60 @@
+use easyerr::*;
+
+#[derive(Error)]
+#[error("failed to parse config", debug = "report")]
+struct ParseError {
+    source: std::io::Error,
+}
+
+#[derive(Error)]
+#[error(debug = "report")]
+enum AppError {
+    #[error("failed to start")]
+    Start { source: ParseError },
+    #[error("invalid port {f0}")]
+    Port(u16),
+}
+
+#[test]
+fn debug_prints_report() {
+    let e = Err::<(), _>(std::io::Error::other("disk on fire"))
+        .context(ParseCtx {})
+        .context(AppCtx::Start)
+        .unwrap_err();
+
+    assert_eq!(
+        format!("{e:?}"),
+        "failed to start\n\nCaused by:\n    0: failed to parse config\n    1: disk on fire"
+    );
+    assert_eq!(format!("{:?}", AppError::Port(0)), "invalid port 0");
+}
+
+#[test]
+fn main_returns_report() {
+    use std::process::{Command, ExitCode, Termination};
+
+    fn main() -> Result<(), AppError> {
+        Err(std::io::Error::other("disk on fire"))
+            .context(ParseCtx {})
+            .context(AppCtx::Start)?;
+
+        Ok(())
+    }
+
+    // `Termination` prints the error to the real stderr, so check it from a child process
+    if std::env::var_os("EASYERR_REPORT_CHILD").is_some() {
+        assert_eq!(main().report(), ExitCode::FAILURE);
+        return;
+    }
+
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["main_returns_report", "--exact", "--nocapture"])
+        .env("EASYERR_REPORT_CHILD", "1")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains(
+        "Error: failed to start\n\nCaused by:\n    0: failed to parse config\n    1: disk on fire\n"
+    ));
+}
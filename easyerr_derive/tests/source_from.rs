@@ -0,0 +1,67 @@
+use std::{io, sync::Arc};
+
+use easyerr::*;
+
+#[derive(Debug, Clone, Error)]
+#[error("failed to read {path}")]
+struct ReadError {
+    #[error(source(from(io::Error, Arc::new)))]
+    source: Arc<io::Error>,
+    path: String,
+}
+
+#[derive(Debug, Error)]
+#[error("io error of kind {f0:?}")]
+struct KindError(io::ErrorKind);
+
+impl From<io::Error> for KindError {
+    fn from(err: io::Error) -> Self {
+        Self(err.kind())
+    }
+}
+
+#[derive(Debug, Error)]
+enum FetchError {
+    #[error("failed to connect")]
+    Connect {
+        #[error(source(from(io::Error, KindError::from)))]
+        source: KindError,
+    },
+    #[error("timed out after {secs}s")]
+    Timeout {
+        #[error(source(from(u64, |_| KindError(io::ErrorKind::TimedOut))))]
+        source: KindError,
+        secs: u64,
+    },
+}
+
+fn not_found() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "oops")
+}
+
+#[test]
+fn main() {
+    let e = Err::<(), _>(not_found())
+        .context(ReadCtx {
+            path: "a.txt".into(),
+        })
+        .unwrap_err();
+    let cloned = e.clone();
+    assert_eq!(format!("{cloned:#}"), "failed to read a.txt: oops");
+
+    let e = Err::<(), _>(not_found())
+        .context(FetchCtx::Connect)
+        .unwrap_err();
+    assert_eq!(
+        format!("{e:#}"),
+        "failed to connect: io error of kind NotFound"
+    );
+
+    let e = Err::<(), _>(3)
+        .context(FetchCtx::Timeout { secs: 3 })
+        .unwrap_err();
+    assert_eq!(
+        format!("{e:#}"),
+        "timed out after 3s: io error of kind TimedOut"
+    );
+}